@@ -1,5 +1,10 @@
 use std::io;
 
+// the prompts live in src/messages.rs so they can be translated
+mod messages;
+
+use messages::{Key, Locale};
+
 /* 
     ============= Statements vs Expressions =============
 
@@ -15,8 +20,8 @@ use std::io;
 // for ease I will define functions BEFORE the main() entry point
 
 // you MUST declare the type of each param in the function signature
-fn greeting(name: String) -> String {
-    return format!("\nHi there {name}", name = name);
+fn greeting(name: String, locale: Locale) -> String {
+    return format!("\n{}", messages::text(locale, Key::Greeting).replace("{name}", &name));
 }
 
 // functions can return values implicitly
@@ -36,15 +41,18 @@ fn main() {
 
     println!("\nmy_value = {}", my_value);
 
+    // the language comes from --lang or the LANG environment variable
+    let locale = Locale::detect();
+
     // Statement
     let mut name = String::new();
 
-    println!("\nEnter your name: ");
+    println!("\n{}", messages::text(locale, Key::EnterName));
 
     io::stdin()
         .read_line(&mut name)
         .expect("Expected a name...");
 
-    println!("{}", greeting(name));
+    println!("{}", greeting(name, locale));
     println!("{}", get_five());
 }
@@ -0,0 +1,70 @@
+// a tiny message catalog so the prompts can be shown in more than one language
+// each language is a function from a message Key to its text
+use std::env;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Locale {
+    English,
+    Spanish,
+}
+
+impl Locale {
+    // LANG looks like "es", "es_ES" or "es_ES.UTF-8" - only the language before any '_', '-' or '.' matters
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let language = tag.split(['_', '-', '.']).next().unwrap_or("");
+
+        match language.to_ascii_lowercase().as_str() {
+            "en" | "c" | "posix" => Some(Locale::English),
+            "es" => Some(Locale::Spanish),
+            _ => None,
+        }
+    }
+
+    // --lang <LANG> on the command line wins, then LC_ALL, then LANG
+    // a language we don't have a translation for is skipped, and English is used when none of them match
+    pub fn detect() -> Locale {
+        let args: Vec<String> = env::args().collect();
+        let from_args = args
+            .iter()
+            .position(|arg| arg == "--lang")
+            .and_then(|index| args.get(index + 1).cloned());
+
+        from_args
+            .into_iter()
+            .chain(["LC_ALL", "LANG"].iter().filter_map(|name| env::var(name).ok()))
+            .filter_map(|value| Locale::from_tag(&value))
+            .next()
+            .unwrap_or(Locale::English)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Key {
+    EnterName,
+    // {name} is replaced with the player's name
+    Greeting,
+}
+
+fn english(key: Key) -> &'static str {
+    match key {
+        Key::EnterName => "Enter your name: ",
+        Key::Greeting => "Hi there {name}",
+    }
+}
+
+// a translation returns None for a key it doesn't have yet
+fn spanish(key: Key) -> Option<&'static str> {
+    match key {
+        Key::EnterName => Some("Escribe tu nombre: "),
+        Key::Greeting => Some("Hola {name}"),
+    }
+}
+
+// the text for a key, falling back to English when the locale is missing it
+pub fn text(locale: Locale, key: Key) -> &'static str {
+    let translated = match locale {
+        Locale::English => None,
+        Locale::Spanish => spanish(key),
+    };
+    translated.unwrap_or_else(|| english(key))
+}
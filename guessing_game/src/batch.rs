@@ -0,0 +1,201 @@
+// scripted play: guesses come from a file or a pipe and the results go out as JSON lines
+// there are no prompts, so stdout only ever contains machine-readable output
+use std::io::{self, BufRead, Write};
+
+use crate::game::Game;
+use crate::input::{self, Command, InputError};
+
+// the process exit codes for a batch run, one for each way it can end
+pub const EXIT_WON: i32 = 0;
+pub const EXIT_LOST: i32 = 1;
+pub const EXIT_INPUT_ERROR: i32 = 3;
+// the input ran out before the number was found or the attempts were used up
+pub const EXIT_UNFINISHED: i32 = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchResult {
+    Won,
+    // out of attempts, or the script said quit
+    Lost,
+    InputError { line: usize, error: InputError },
+    Unfinished,
+}
+
+impl BatchResult {
+    pub fn exit_code(&self) -> i32 {
+        match *self {
+            BatchResult::Won => EXIT_WON,
+            BatchResult::Lost => EXIT_LOST,
+            BatchResult::InputError { .. } => EXIT_INPUT_ERROR,
+            BatchResult::Unfinished => EXIT_UNFINISHED,
+        }
+    }
+}
+
+fn error_name(error: &InputError) -> &'static str {
+    match *error {
+        InputError::Empty => "empty",
+        InputError::Negative => "negative",
+        InputError::OutOfRange { .. } => "out_of_range",
+        InputError::Overflow => "overflow",
+        InputError::NotANumber(_) => "not_a_number",
+        InputError::NotAnAnswer => "not_an_answer",
+        InputError::Contradiction => "contradiction",
+    }
+}
+
+// write text as a JSON string, with the quotes and escapes that needs
+pub fn json_string(text: &str) -> String {
+    let mut json = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            // any other control character is written as a \u escape
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+// play one game with guesses read line by line from input, writing a JSON object per line to out
+// blank lines are skipped; anything else that is not a guess (or quit) stops the run with an error
+pub fn run<R: BufRead, W: Write>(game: &mut Game, input: R, mut out: W) -> io::Result<BatchResult> {
+    let mut result = BatchResult::Unfinished;
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let guess = match input::parse_command(&line, game.low(), game.high()) {
+            Ok(Command::Guess(guess)) => guess,
+            Ok(Command::Quit) => {
+                result = BatchResult::Lost;
+                break;
+            }
+            Err(error) => {
+                writeln!(
+                    out,
+                    "{{\"error\":\"{}\",\"line\":{},\"input\":{},\"message\":{}}}",
+                    error_name(&error),
+                    index + 1,
+                    json_string(line.trim()),
+                    json_string(&error.to_string())
+                )?;
+                return Ok(BatchResult::InputError { line: index + 1, error });
+            }
+        };
+
+        let outcome = game.guess(guess);
+        // {{ and }} are how format strings write a literal { and }
+        writeln!(
+            out,
+            "{{\"attempt\":{},\"guess\":{},\"outcome\":\"{}\"}}",
+            game.attempts(),
+            guess,
+            outcome.as_json_str()
+        )?;
+
+        if game.is_won() {
+            result = BatchResult::Won;
+            break;
+        }
+        if game.is_lost() {
+            result = BatchResult::Lost;
+            break;
+        }
+    }
+
+    let name = match result {
+        BatchResult::Won => "won",
+        BatchResult::Lost => "lost",
+        _ => "unfinished",
+    };
+    writeln!(
+        out,
+        "{{\"result\":\"{}\",\"attempts\":{},\"secret\":{}}}",
+        name,
+        game.attempts(),
+        game.secret()
+    )?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    // run a whole script against the secret 42 in 1..=100, giving back the result and the JSON lines
+    fn play(script: &str, max_attempts: Option<u32>) -> (BatchResult, Vec<String>) {
+        let mut game = Game::new(42, Config::new(1, 100, max_attempts).unwrap());
+        let mut out = Vec::new();
+        let result = run(&mut game, script.as_bytes(), &mut out).unwrap();
+        let lines = String::from_utf8(out).unwrap().lines().map(String::from).collect();
+        (result, lines)
+    }
+
+    #[test]
+    fn a_winning_script() {
+        let (result, lines) = play("50\n\n25\n  42  \n99\n", None);
+
+        assert_eq!(result, BatchResult::Won);
+        assert_eq!(result.exit_code(), EXIT_WON);
+        // the blank line is skipped and nothing after the winning guess is read
+        assert_eq!(
+            lines,
+            vec![
+                r#"{"attempt":1,"guess":50,"outcome":"too_large"}"#,
+                r#"{"attempt":2,"guess":25,"outcome":"too_small"}"#,
+                r#"{"attempt":3,"guess":42,"outcome":"correct"}"#,
+                r#"{"result":"won","attempts":3,"secret":42}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn a_malformed_line_stops_the_run() {
+        let (result, lines) = play("50\nfifty \"2\"\n42\n", None);
+
+        assert_eq!(
+            result,
+            BatchResult::InputError { line: 2, error: InputError::NotANumber(String::from("fifty \"2\"")) }
+        );
+        assert_eq!(result.exit_code(), EXIT_INPUT_ERROR);
+        assert_eq!(
+            lines,
+            vec![
+                r#"{"attempt":1,"guess":50,"outcome":"too_large"}"#,
+                r#"{"error":"not_a_number","line":2,"input":"fifty \"2\"","message":"'fifty \"2\"' is not a number."}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn losing_quitting_and_running_out_of_input() {
+        let (result, lines) = play("1\n2\n3\n", Some(2));
+        assert_eq!(result, BatchResult::Lost);
+        assert_eq!(lines.last().unwrap(), r#"{"result":"lost","attempts":2,"secret":42}"#);
+
+        let (result, lines) = play("10\nquit\n42\n", None);
+        assert_eq!(result, BatchResult::Lost);
+        assert_eq!(lines.last().unwrap(), r#"{"result":"lost","attempts":1,"secret":42}"#);
+
+        let (result, lines) = play("10\n", None);
+        assert_eq!(result.exit_code(), EXIT_UNFINISHED);
+        assert_eq!(lines.last().unwrap(), r#"{"result":"unfinished","attempts":1,"secret":42}"#);
+    }
+
+    #[test]
+    fn json_strings_are_escaped() {
+        assert_eq!(json_string("say \"hi\"\\\n\t\u{1}"), r#""say \"hi\"\\\n\t\u0001""#);
+    }
+}
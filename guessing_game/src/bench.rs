@@ -0,0 +1,126 @@
+// run lots of computer-played games and summarise how many guesses each strategy needed
+use std::collections::BTreeMap;
+
+use rand::RngCore;
+
+use crate::config::Config;
+use crate::session::Session;
+use crate::solver::{self, SolveError, StrategyKind};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchReport {
+    pub strategy: StrategyKind,
+    pub games: u32,
+    pub mean: f64,
+    pub worst: u32,
+    // number of guesses -> how many games needed exactly that many
+    // a BTreeMap keeps its keys sorted, which is the order we want to print them in
+    pub distribution: BTreeMap<u32, u32>,
+}
+
+// play `games` rounds with one strategy
+// the attempt limit is ignored so that every game is played until the number is found
+pub fn run<R: RngCore>(strategy: StrategyKind, config: Config, games: u32, rng: R) -> Result<BenchReport, SolveError> {
+    let config = Config { max_attempts: None, ..config };
+    let mut session = Session::new(config, rng);
+    let mut player = strategy.build();
+
+    let mut total: u64 = 0;
+    let mut worst = 0;
+    let mut distribution = BTreeMap::new();
+
+    for _ in 0..games {
+        let mut game = session.next_game();
+        solver::solve(&mut game, player.as_mut(), session.rng())?;
+
+        let attempts = game.attempts();
+        total += u64::from(attempts);
+        worst = worst.max(attempts);
+        // entry().or_insert() gives us the count for this key, adding a 0 first if it is new
+        *distribution.entry(attempts).or_insert(0) += 1;
+    }
+
+    let mean = if games == 0 { 0.0 } else { total as f64 / f64::from(games) };
+
+    Ok(BenchReport {
+        strategy,
+        games,
+        mean,
+        worst,
+        distribution,
+    })
+}
+
+impl BenchReport {
+    // group the distribution into at most `rows` equally wide ranges of guess counts
+    // each item is (fewest guesses, most guesses, number of games) - short distributions are returned as they are
+    pub fn buckets(&self, rows: u32) -> Vec<(u32, u32, u32)> {
+        let first = match self.distribution.keys().next() {
+            Some(&first) => first,
+            None => return Vec::new(),
+        };
+
+        let span = self.worst - first + 1;
+        // dividing and rounding up, so that `rows` buckets always cover the whole span
+        let width = ((span + rows - 1) / rows.max(1)).max(1);
+
+        let mut buckets: Vec<(u32, u32, u32)> = Vec::new();
+        for (&attempts, &count) in &self.distribution {
+            let start = first + (attempts - first) / width * width;
+            match buckets.last_mut() {
+                Some(last) if last.0 == start => last.2 += count,
+                _ => buckets.push((start, (start + width - 1).min(self.worst), count)),
+            }
+        }
+
+        buckets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session;
+
+    fn report(strategy: StrategyKind, games: u32) -> BenchReport {
+        let config = Config::new(1, 100, Some(3)).unwrap();
+        run(strategy, config, games, session::rng_from_seed(Some(42))).unwrap()
+    }
+
+    #[test]
+    fn every_game_is_played_to_the_end() {
+        let report = report(StrategyKind::BinarySearch, 200);
+
+        // the limit of 3 guesses is ignored, so no game can be cut short
+        assert_eq!(report.distribution.values().sum::<u32>(), 200);
+        assert_eq!(report.distribution.keys().last(), Some(&report.worst));
+        assert!(report.worst <= solver::worst_case(1, 100));
+        assert!(report.mean >= 1.0 && report.mean <= f64::from(report.worst));
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_report() {
+        assert_eq!(report(StrategyKind::Random, 50), report(StrategyKind::Random, 50));
+    }
+
+    #[test]
+    fn buckets_cover_every_game() {
+        let report = report(StrategyKind::Linear, 300);
+
+        let buckets = report.buckets(10);
+        assert!(buckets.len() <= 10);
+        assert_eq!(buckets.iter().map(|bucket| bucket.2).sum::<u32>(), 300);
+        assert_eq!(buckets.last().map(|bucket| bucket.1), Some(report.worst));
+        // the buckets follow on from each other without gaps
+        for pair in buckets.windows(2) {
+            assert!(pair[0].1 < pair[1].0);
+        }
+    }
+
+    #[test]
+    fn no_games_gives_an_empty_report() {
+        let report = report(StrategyKind::BinarySearch, 0);
+        assert_eq!(report.mean, 0.0);
+        assert!(report.buckets(10).is_empty());
+    }
+}
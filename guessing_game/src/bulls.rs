@@ -0,0 +1,258 @@
+// Bulls and Cows: the secret is a code of N different digits
+// every guess is scored with bulls (right digit in the right place) and cows (right digit in the wrong place)
+use std::fmt;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+// with no repeated digits a code can be at most 10 digits long
+pub const MAX_LENGTH: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Score {
+    pub bulls: u32,
+    pub cows: u32,
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {}, {} {}",
+            self.bulls,
+            if self.bulls == 1 { "bull" } else { "bulls" },
+            self.cows,
+            if self.cows == 1 { "cow" } else { "cows" }
+        )
+    }
+}
+
+// why a line of input is not a valid code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodeError {
+    Empty,
+    WrongLength { expected: usize, got: usize },
+    NotADigit(char),
+    RepeatedDigit(char),
+}
+
+impl fmt::Display for CodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CodeError::Empty => write!(f, "Please type a code (or 'quit' to give up)."),
+            CodeError::WrongLength { expected, got } => {
+                write!(f, "The code has {} digits, but you typed {}.", expected, got)
+            }
+            CodeError::NotADigit(c) => write!(f, "'{}' is not a digit.", c),
+            CodeError::RepeatedDigit(c) => {
+                write!(f, "The digit {} is used more than once - every digit is different.", c)
+            }
+        }
+    }
+}
+
+// turn text like "0471" into its digits, checking the length and that no digit repeats
+pub fn parse_code(text: &str, length: usize) -> Result<Vec<u8>, CodeError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(CodeError::Empty);
+    }
+
+    let mut digits = Vec::new();
+    for c in text.chars() {
+        // to_digit(10) gives Some(0..=9) for '0'..='9' and None for anything else
+        let digit = c.to_digit(10).ok_or(CodeError::NotADigit(c))? as u8;
+        if digits.contains(&digit) {
+            return Err(CodeError::RepeatedDigit(c));
+        }
+        digits.push(digit);
+    }
+
+    if digits.len() != length {
+        return Err(CodeError::WrongLength {
+            expected: length,
+            got: digits.len(),
+        });
+    }
+
+    Ok(digits)
+}
+
+// count the bulls and cows of a guess against the secret
+// parse_code never lets a digit repeat, but score() is fair about repeats anyway:
+// each digit of the secret can only be matched once, so 1111 against 1234 is 1 bull and no cows
+pub fn score(secret: &[u8], guess: &[u8]) -> Score {
+    let mut bulls = 0;
+    // how often each digit appears in the secret and in the guess, leaving out the bulls
+    let mut in_secret = [0u32; 10];
+    let mut in_guess = [0u32; 10];
+
+    for (position, &digit) in guess.iter().enumerate() {
+        match secret.get(position) {
+            Some(&wanted) if wanted == digit => bulls += 1,
+            other => {
+                in_guess[digit as usize % 10] += 1;
+                if let Some(&wanted) = other {
+                    in_secret[wanted as usize % 10] += 1;
+                }
+            }
+        }
+    }
+    // a secret longer than the guess still has digits the guess can match out of place
+    for &wanted in secret.iter().skip(guess.len()) {
+        in_secret[wanted as usize % 10] += 1;
+    }
+
+    let cows = in_secret.iter().zip(in_guess.iter()).map(|(&s, &g)| s.min(g)).sum();
+    Score { bulls, cows }
+}
+
+// one round of Bulls and Cows - the digit version of Game
+#[derive(Debug)]
+pub struct CodeGame {
+    secret: Vec<u8>,
+    max_attempts: Option<u32>,
+    history: Vec<(Vec<u8>, Score)>,
+}
+
+impl CodeGame {
+    // a game with a known secret, e.g. CodeGame::new("1234", None)
+    pub fn new(secret: &str, max_attempts: Option<u32>) -> Result<CodeGame, CodeError> {
+        let secret = parse_code(secret, secret.trim().chars().count())?;
+        Ok(CodeGame {
+            secret,
+            max_attempts,
+            history: Vec::new(),
+        })
+    }
+
+    // a game with a random code of `length` different digits (length is capped at MAX_LENGTH)
+    pub fn random<R: Rng + ?Sized>(length: usize, max_attempts: Option<u32>, rng: &mut R) -> CodeGame {
+        let mut digits: Vec<u8> = (0..10).collect();
+        // shuffle all ten digits and keep the first few - that guarantees no repeats
+        digits.shuffle(rng);
+        digits.truncate(length.clamp(1, MAX_LENGTH));
+
+        CodeGame {
+            secret: digits,
+            max_attempts,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn length(&self) -> usize {
+        self.secret.len()
+    }
+
+    // check and score one guess - an invalid code does not use up an attempt
+    pub fn guess(&mut self, text: &str) -> Result<Score, CodeError> {
+        let guess = parse_code(text, self.length())?;
+        let result = score(&self.secret, &guess);
+        self.history.push((guess, result));
+        Ok(result)
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.history.len() as u32
+    }
+
+    pub fn attempts_left(&self) -> Option<u32> {
+        self.max_attempts.map(|max| max.saturating_sub(self.attempts()))
+    }
+
+    pub fn is_won(&self) -> bool {
+        let length = self.length() as u32;
+        self.history.iter().any(|&(_, score)| score.bulls == length)
+    }
+
+    pub fn is_lost(&self) -> bool {
+        !self.is_won() && self.attempts_left() == Some(0)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.is_lost()
+    }
+
+    // the secret code as text, for revealing it at the end
+    pub fn secret(&self) -> String {
+        self.secret.iter().map(|digit| digit.to_string()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scored(secret: &[u8], guess: &[u8]) -> (u32, u32) {
+        let score = score(secret, guess);
+        (score.bulls, score.cows)
+    }
+
+    #[test]
+    fn bulls_and_cows_on_known_pairs() {
+        assert_eq!(scored(&[1, 2, 3, 4], &[1, 2, 3, 4]), (4, 0));
+        assert_eq!(scored(&[1, 2, 3, 4], &[4, 3, 2, 1]), (0, 4));
+        assert_eq!(scored(&[1, 2, 3, 4], &[1, 3, 5, 6]), (1, 1));
+        assert_eq!(scored(&[1, 2, 3, 4], &[5, 6, 7, 8]), (0, 0));
+        assert_eq!(scored(&[0, 4, 7, 1], &[0, 1, 4, 9]), (1, 2));
+    }
+
+    #[test]
+    fn a_repeated_digit_is_only_matched_once() {
+        assert_eq!(scored(&[1, 2, 3, 4], &[1, 1, 1, 1]), (1, 0));
+        assert_eq!(scored(&[1, 2, 3, 4], &[2, 2, 1, 1]), (1, 1));
+        assert_eq!(scored(&[1, 1, 2, 2], &[2, 2, 1, 1]), (0, 4));
+        assert_eq!(scored(&[1, 1, 2, 2], &[1, 2, 1, 3]), (1, 2));
+    }
+
+    #[test]
+    fn parse_code_gives_the_right_error() {
+        assert_eq!(parse_code(" 0471 ", 4), Ok(vec![0, 4, 7, 1]));
+        assert_eq!(parse_code("   ", 4), Err(CodeError::Empty));
+        assert_eq!(parse_code("123", 4), Err(CodeError::WrongLength { expected: 4, got: 3 }));
+        assert_eq!(parse_code("12345", 4), Err(CodeError::WrongLength { expected: 4, got: 5 }));
+        assert_eq!(parse_code("12a4", 4), Err(CodeError::NotADigit('a')));
+        assert_eq!(parse_code("1-34", 4), Err(CodeError::NotADigit('-')));
+        assert_eq!(parse_code("1231", 4), Err(CodeError::RepeatedDigit('1')));
+    }
+
+    #[test]
+    fn a_game_is_won_by_the_right_code() {
+        let mut game = CodeGame::new("1234", Some(5)).unwrap();
+        assert_eq!(game.guess("1243"), Ok(Score { bulls: 2, cows: 2 }));
+        // an invalid code does not use up an attempt
+        assert_eq!(game.guess("12"), Err(CodeError::WrongLength { expected: 4, got: 2 }));
+        assert_eq!(game.attempts(), 1);
+        assert!(!game.is_over());
+
+        assert_eq!(game.guess("1234"), Ok(Score { bulls: 4, cows: 0 }));
+        assert!(game.is_won());
+        assert!(!game.is_lost());
+        assert_eq!(game.attempts_left(), Some(3));
+    }
+
+    #[test]
+    fn a_game_is_lost_when_the_attempts_run_out() {
+        let mut game = CodeGame::new("1234", Some(2)).unwrap();
+        game.guess("5678").unwrap();
+        assert!(!game.is_over());
+        game.guess("4321").unwrap();
+
+        assert!(game.is_lost());
+        assert!(game.is_over());
+        assert_eq!(game.attempts_left(), Some(0));
+        assert_eq!(game.secret(), "1234");
+    }
+
+    #[test]
+    fn random_codes_never_repeat_a_digit() {
+        let mut rng = crate::session::rng_from_seed(Some(3));
+        for length in 1..=MAX_LENGTH {
+            let game = CodeGame::random(length, None, &mut rng);
+            assert_eq!(parse_code(&game.secret(), length).map(|digits| digits.len()), Ok(length));
+        }
+        // a length of zero or past ten digits is clamped
+        assert_eq!(CodeGame::random(0, None, &mut rng).length(), 1);
+        assert_eq!(CodeGame::random(12, None, &mut rng).length(), MAX_LENGTH);
+    }
+}
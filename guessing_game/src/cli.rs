@@ -0,0 +1,617 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::bulls::MAX_LENGTH;
+use crate::config::{Config, ConfigError, Difficulty};
+use crate::hint::Thresholds;
+use crate::http;
+use crate::messages::Locale;
+use crate::number::Number;
+use crate::scores;
+use crate::server::PlayMode;
+use crate::solver::StrategyKind;
+use crate::stats::Filter;
+use crate::ulam::LiePolicy;
+use crate::variant::VariantKind;
+
+// the number games other than the original whole numbers from --min to --max
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NumberMode {
+    U64(Config<u64>),
+    I64(Config<i64>),
+    // a decimal secret - any guess within the tolerance counts as correct
+    F64 { config: Config<f64>, epsilon: f64 },
+}
+
+// everything the player can set on the command line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub difficulty: Difficulty,
+    pub config: Config,
+    // play with 64-bit, negative or decimal numbers instead of `config` - None is the usual u32 game
+    pub numbers: Option<NumberMode>,
+    // a fixed seed makes the secret numbers repeatable
+    pub seed: Option<u64>,
+    // the name saved with each finished round - asked for if it is not given
+    pub player: Option<String>,
+    // None means the default location from scores::default_path()
+    pub score_file: Option<PathBuf>,
+    // None means the default location from profiles::default_path()
+    pub profile_file: Option<PathBuf>,
+    // the `profile <NAME>` subcommand: show a player's record and achievements
+    pub show_profile: Option<String>,
+    // print the high-score table instead of playing
+    pub show_scores: bool,
+    // let the computer play instead of reading guesses from the player
+    pub auto: bool,
+    // which computer player to use - None means binary search (or every strategy for --bench)
+    pub strategy: Option<StrategyKind>,
+    // run this many computer-played games per strategy and print a summary
+    pub bench: Option<u32>,
+    // show hot/cold hints using these bands - None turns hints off
+    pub hints: Option<Thresholds>,
+    // run a multiplayer server on this address instead of playing in the terminal
+    pub serve: Option<String>,
+    pub mode: PlayMode,
+    // serve the HTTP/JSON API on this (loopback) address instead of playing in the terminal
+    pub http: Option<String>,
+    // forget an HTTP session after this many seconds without a request
+    pub session_timeout: u64,
+    // write every prompt, guess and outcome to this file
+    pub record: Option<PathBuf>,
+    // the `stats` subcommand: summarise the score file, limited to the rounds that pass the filter
+    pub stats: Option<Filter>,
+    // the `replay <FILE>` subcommand: check a recorded transcript against the game
+    pub replay: Option<PathBuf>,
+    // read guesses from this file ("-" for stdin) and print JSON lines instead of prompts
+    pub batch: Option<PathBuf>,
+    // use the full-screen interface (when stdout is a terminal)
+    pub tui: bool,
+    // which rules the number game is played with
+    pub variant: VariantKind,
+    // play Bulls and Cows with a code of this many digits instead of the number game
+    pub digits: Option<usize>,
+    // play Ulam's game: the answers may include up to this many lies
+    pub lies: Option<u32>,
+    pub liar: LiePolicy,
+    // countdown mode: each round must be won within this many seconds
+    pub time_limit: Option<u64>,
+    // speed-run mode: play this many rounds back to back against the clock
+    pub speedrun: Option<u32>,
+    // the language for the game's messages - None means use LANG
+    pub locale: Option<Locale>,
+    pub help: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliError {
+    UnknownOption(String),
+    MissingValue(String),
+    InvalidValue { option: String, value: String },
+    Config(ConfigError),
+    // a bad range for one of the --number types (ConfigError only describes u32 ranges)
+    InvalidRange(String),
+    // two options that cannot be used together, like a --variant and a mode it does not support
+    Conflict { option: String, other: String },
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CliError::UnknownOption(ref option) => write!(f, "unknown option '{}'", option),
+            CliError::MissingValue(ref option) => write!(f, "'{}' needs a value", option),
+            CliError::InvalidValue { ref option, ref value } => {
+                write!(f, "'{}' is not a valid value for '{}'", value, option)
+            }
+            CliError::Config(ref e) => write!(f, "invalid game settings: {}", e),
+            CliError::InvalidRange(ref e) => write!(f, "invalid game settings: {}", e),
+            CliError::Conflict { ref option, ref other } => write!(f, "'{}' cannot be used with '{}'", option, other),
+        }
+    }
+}
+
+// the From trait lets the ? operator turn a ConfigError into a CliError for us
+impl From<ConfigError> for CliError {
+    fn from(e: ConfigError) -> CliError {
+        CliError::Config(e)
+    }
+}
+
+pub const USAGE: &str = "\
+Usage: guessing_game [OPTIONS]
+       guessing_game replay <FILE>
+       guessing_game profile <NAME>
+       guessing_game stats [-d <LEVEL>] [--name <NAME>] [--from <DATE>] [--to <DATE>]
+
+Options:
+  -d, --difficulty <LEVEL>  easy (1-50), normal (1-100), hard (1-1000) or custom
+      --min <N>             lowest possible secret number
+      --max <N>             highest possible secret number
+      --attempts <N>        how many guesses the player gets
+      --unlimited           let the player keep guessing forever
+      --number <TYPE>       u32 (default), u64, i64 (allows negative numbers) or f64 (decimals);
+                            the wider types default to no attempt limit and are played
+                            without hints, timers, transcripts, scores or profiles
+      --epsilon <E>         with --number f64, how close a guess must be to count (default: 0.05)
+      --seed <N>            use a fixed random seed so every run picks the same numbers
+      --name <NAME>         the player name saved with your scores
+      --scores              show the best results for each difficulty and exit
+      --score-file <PATH>   where scores are kept (default: ~/.guessing_game_scores)
+      --profile-file <PATH> where player profiles are kept (default: ~/.guessing_game_profiles)
+      --from <DATE>         with stats, only count rounds on or after DATE (YYYY-MM-DD, UTC)
+      --to <DATE>           with stats, only count rounds on or before DATE
+      --auto                watch the computer play
+      --strategy <NAME>     computer player: binary (default), random or linear
+      --bench <GAMES>       play GAMES computer games per strategy and report the results
+      --variant <NAME>      rules for the number game: classic (default), shrink (every answer
+                            narrows the range you may guess in), multi (find 3 secrets) or
+                            reverse (you pick the number and the computer guesses it)
+      --digits <N>          play Bulls and Cows: find a code of N different digits (1-10)
+      --lies <K>            Ulam's game: up to K of the answers may be lies (works with --auto)
+                            (unlimited guesses unless --attempts is given)
+      --liar <POLICY>       how lies are chosen: random (default) or adversarial
+      --time-limit <SECS>   countdown mode: each round has SECS seconds - the clock is checked
+                            when a guess is entered, so a late guess loses the round but the
+                            game never interrupts you while you are typing
+      --speedrun <ROUNDS>   play ROUNDS rounds back to back and report the times
+      --lang <LANG>         language for the messages: en or es (default: from LANG)
+      --tui                 full-screen interface (falls back to prompts if not a terminal)
+      --hints               say how close each guess was (hot, warm, cold or freezing)
+      --hint-bands <H,W,C>  where the hot, warm and cold bands end, as fractions of the
+                            range (default: 0.05,0.15,0.35) - implies --hints
+      --serve <ADDR>        host a multiplayer game on a loopback address, e.g. --serve 127.0.0.1:7878
+      --mode <MODE>         multiplayer mode: race (default) or turns
+      --http <ADDR>         serve the HTTP/JSON API on a loopback address, e.g. --http 127.0.0.1:8080
+      --session-timeout <SECS>
+                            forget an HTTP session after SECS seconds unused (default: 300)
+      --record <FILE>       save a transcript of the session that `replay` can check later
+      --batch <FILE>        read guesses from FILE (- for stdin) and print JSON lines;
+                            exits 0 on a win, 1 on a loss, 3 on bad input, 4 if input runs out
+  -h, --help                show this message";
+
+// parse the command line arguments (without the program name)
+// taking an Iterator instead of std::env::args() directly means we can pass in any list of strings
+pub fn parse_args<I>(args: I) -> Result<Options, CliError>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+
+    let mut difficulty = None;
+    let mut low = None;
+    let mut high = None;
+    let mut max_attempts = None;
+    let mut unlimited = false;
+    let mut seed = None;
+    let mut player = None;
+    let mut score_file = None;
+    let mut profile_file = None;
+    let mut show_profile = None;
+    let mut show_scores = false;
+    let mut auto = false;
+    let mut strategy = None;
+    let mut bench = None;
+    let mut hints = None;
+    let mut serve = None;
+    let mut mode = PlayMode::Race;
+    let mut http = None;
+    let mut session_timeout = http::DEFAULT_TIMEOUT.as_secs();
+    let mut record = None;
+    let mut replay = None;
+    let mut stats = false;
+    let mut from = None;
+    let mut until = None;
+    let mut batch = None;
+    let mut tui = false;
+    let mut variant = VariantKind::default();
+    let mut digits = None;
+    let mut lies = None;
+    let mut liar = LiePolicy::Random(0.3);
+    let mut time_limit = None;
+    let mut speedrun = None;
+    let mut locale = None;
+    let mut number = String::from("u32");
+    let mut epsilon = 0.05;
+    let mut help = false;
+
+    // while let keeps looping for as long as next() returns Some
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-d" | "--difficulty" => {
+                let value = value_for(&arg, &mut args)?;
+                difficulty = Some(value.parse().map_err(|_| invalid(&arg, &value))?);
+            }
+            // the bounds are kept as text until we know which --number type to parse them as
+            "--min" => low = Some(value_for(&arg, &mut args)?),
+            "--max" => high = Some(value_for(&arg, &mut args)?),
+            "--number" => number = value_for(&arg, &mut args)?.to_ascii_lowercase(),
+            "--epsilon" => {
+                let value = value_for(&arg, &mut args)?;
+                match value.trim().parse::<f64>() {
+                    Ok(e) if e > 0.0 && e.is_finite() => epsilon = e,
+                    _ => return Err(invalid(&arg, &value)),
+                }
+            }
+            "--attempts" => max_attempts = Some(number_for(&arg, &mut args)?),
+            "--unlimited" => unlimited = true,
+            "--seed" => seed = Some(number_for(&arg, &mut args)?),
+            "--name" => player = Some(value_for(&arg, &mut args)?),
+            "--scores" => show_scores = true,
+            "--score-file" => score_file = Some(PathBuf::from(value_for(&arg, &mut args)?)),
+            "--profile-file" => profile_file = Some(PathBuf::from(value_for(&arg, &mut args)?)),
+            "--auto" => auto = true,
+            "--strategy" => {
+                let value = value_for(&arg, &mut args)?;
+                strategy = Some(value.parse().map_err(|_| invalid(&arg, &value))?);
+            }
+            "--bench" => bench = Some(number_for(&arg, &mut args)?),
+            "--tui" => tui = true,
+            "--lang" => {
+                let value = value_for(&arg, &mut args)?;
+                locale = Some(value.parse().map_err(|_| invalid(&arg, &value))?);
+            }
+            "--time-limit" => time_limit = Some(number_for(&arg, &mut args)?),
+            "--speedrun" => speedrun = Some(number_for(&arg, &mut args)?),
+            "--lies" => lies = Some(number_for(&arg, &mut args)?),
+            "--liar" => {
+                let value = value_for(&arg, &mut args)?;
+                liar = value.parse().map_err(|_| invalid(&arg, &value))?;
+            }
+            "--variant" => {
+                let value = value_for(&arg, &mut args)?;
+                variant = value.parse().map_err(|_| invalid(&arg, &value))?;
+            }
+            "--digits" => {
+                let value = value_for(&arg, &mut args)?;
+                match value.trim().parse() {
+                    Ok(n) if (1..=MAX_LENGTH).contains(&n) => digits = Some(n),
+                    _ => return Err(invalid(&arg, &value)),
+                }
+            }
+            // get_or_insert_with keeps any bands that were already set by --hint-bands
+            "--hints" => {
+                hints.get_or_insert_with(Thresholds::default);
+            }
+            "--serve" => serve = Some(value_for(&arg, &mut args)?),
+            "--http" => http = Some(value_for(&arg, &mut args)?),
+            "--session-timeout" => session_timeout = number_for(&arg, &mut args)?,
+            "--mode" => {
+                let value = value_for(&arg, &mut args)?;
+                mode = value.parse().map_err(|_| invalid(&arg, &value))?;
+            }
+            "--hint-bands" => {
+                let value = value_for(&arg, &mut args)?;
+                hints = Some(value.parse().map_err(|_| invalid(&arg, &value))?);
+            }
+            "--record" => record = Some(PathBuf::from(value_for(&arg, &mut args)?)),
+            "--batch" => batch = Some(PathBuf::from(value_for(&arg, &mut args)?)),
+            "--from" => {
+                let value = value_for(&arg, &mut args)?;
+                from = Some(scores::parse_date(&value).ok_or_else(|| invalid(&arg, &value))?);
+            }
+            // --to includes the whole of its day, so the filter stops at the start of the next one
+            "--to" => {
+                let value = value_for(&arg, &mut args)?;
+                let start = scores::parse_date(&value).and_then(|start| start.checked_add(86_400));
+                until = Some(start.ok_or_else(|| invalid(&arg, &value))?);
+            }
+            "stats" => stats = true,
+            "profile" => show_profile = Some(value_for(&arg, &mut args)?),
+            "replay" => replay = Some(PathBuf::from(value_for(&arg, &mut args)?)),
+            "-h" | "--help" => help = true,
+            _ => return Err(CliError::UnknownOption(arg)),
+        }
+    }
+
+    // stats only filters by difficulty when one was asked for, so it is read before the default is filled in
+    let stats = if stats {
+        Some(Filter {
+            difficulty,
+            player: player.clone(),
+            from,
+            until,
+        })
+    } else {
+        None
+    };
+
+    // the full-screen interface has no countdown, so the two can't be combined
+    if time_limit.is_some() {
+        refuse("--time-limit", &[("--tui", tui)])?;
+    }
+
+    // the other variants are only played by the main prompt loop, so the modes with their own loops are refused
+    if variant != VariantKind::Classic {
+        refuse(
+            "--variant",
+            &[
+                ("--number", number != "u32"),
+                ("--digits", digits.is_some()),
+                ("--lies", lies.is_some()),
+                ("--auto", auto),
+                ("--bench", bench.is_some()),
+                ("--batch", batch.is_some()),
+                ("--serve", serve.is_some()),
+                ("--http", http.is_some()),
+            ],
+        )?;
+    }
+    // hints, transcripts, scores and profiles are all worked out from the guesses at a single secret number
+    if !variant.one_secret() {
+        refuse(
+            "--variant",
+            &[
+                ("--hints", hints.is_some()),
+                ("--record", record.is_some()),
+                ("--name", player.is_some()),
+                ("--score-file", score_file.is_some()),
+                ("--profile-file", profile_file.is_some()),
+            ],
+        )?;
+    }
+
+    // Bulls and Cows and Ulam's game have prompt loops of their own, which have none of the number game's extras
+    let own_loop_conflicts = [
+        ("--bench", bench.is_some()),
+        ("--batch", batch.is_some()),
+        ("--serve", serve.is_some()),
+        ("--http", http.is_some()),
+        ("--tui", tui),
+        ("--hints", hints.is_some()),
+        ("--record", record.is_some()),
+        ("--time-limit", time_limit.is_some()),
+        ("--speedrun", speedrun.is_some()),
+    ];
+    if digits.is_some() {
+        refuse("--digits", &[("--lies", lies.is_some()), ("--auto", auto)])?;
+        refuse("--digits", &own_loop_conflicts)?;
+    }
+    if lies.is_some() {
+        refuse("--lies", &own_loop_conflicts)?;
+    }
+
+    // the chosen preset (normal if none was given) supplies anything the player did not set
+    let preset = Config::preset(difficulty.unwrap_or(Difficulty::Normal));
+
+    // any setting on top of a preset turns the game into a custom one - and so does playing it by other rules
+    let customised =
+        low.is_some() || high.is_some() || max_attempts.is_some() || unlimited || variant != VariantKind::Classic;
+    let difficulty = if customised {
+        Difficulty::Custom
+    } else {
+        difficulty.unwrap_or(Difficulty::Normal)
+    };
+
+    // the wider number types have no preset, so they only get an attempt limit if one was asked for
+    let numbers = match number.as_str() {
+        "u32" => None,
+        "u64" => Some(NumberMode::U64(wide_config(&low, &high, 1, 1_000_000_000_000, max_attempts)?)),
+        "i64" => Some(NumberMode::I64(wide_config(&low, &high, -1000, 1000, max_attempts)?)),
+        "f64" => Some(NumberMode::F64 {
+            config: wide_config(&low, &high, 0.0, 100.0, max_attempts)?,
+            epsilon,
+        }),
+        _ => return Err(invalid("--number", &number)),
+    };
+
+    // the wider types are played by a loop of their own, which has none of the extras the u32 game has
+    if numbers.is_some() {
+        refuse(
+            "--number",
+            &[
+                ("--digits", digits.is_some()),
+                ("--lies", lies.is_some()),
+                ("--auto", auto),
+                ("--bench", bench.is_some()),
+                ("--batch", batch.is_some()),
+                ("--serve", serve.is_some()),
+                ("--http", http.is_some()),
+                ("--hints", hints.is_some()),
+                ("--record", record.is_some()),
+                ("--tui", tui),
+                ("--time-limit", time_limit.is_some()),
+                ("--speedrun", speedrun.is_some()),
+                ("--name", player.is_some()),
+                ("--score-file", score_file.is_some()),
+                ("--profile-file", profile_file.is_some()),
+            ],
+        )?;
+    }
+
+    let low = match (numbers, low) {
+        (None, Some(ref value)) => Some(parse_bound("--min", value)?),
+        _ => None,
+    };
+    let high = match (numbers, high) {
+        (None, Some(ref value)) => Some(parse_bound("--max", value)?),
+        _ => None,
+    };
+
+    // a preset's limit is sized for truthful answers - with lies even the solver can run out - so Ulam's game
+    // only has a limit when --attempts asks for one
+    let max_attempts = if unlimited || (lies.is_some() && max_attempts.is_none()) {
+        None
+    } else {
+        max_attempts.or(preset.max_attempts)
+    };
+    let config = Config::new(low.unwrap_or(preset.low), high.unwrap_or(preset.high), max_attempts)?;
+
+    Ok(Options {
+        difficulty,
+        config,
+        numbers,
+        seed,
+        player,
+        score_file,
+        profile_file,
+        show_profile,
+        show_scores,
+        auto,
+        strategy,
+        bench,
+        hints,
+        serve,
+        mode,
+        http,
+        session_timeout,
+        record,
+        stats,
+        replay,
+        batch,
+        tui,
+        variant,
+        digits,
+        lies,
+        liar,
+        time_limit,
+        speedrun,
+        locale,
+        help,
+    })
+}
+
+// take the value that follows an option like --min
+fn value_for<I>(option: &str, args: &mut I) -> Result<String, CliError>
+where
+    I: Iterator<Item = String>,
+{
+    args.next().ok_or_else(|| CliError::MissingValue(option.to_string()))
+}
+
+// take the value that follows an option and parse it into whatever number type is needed
+fn number_for<I, N>(option: &str, args: &mut I) -> Result<N, CliError>
+where
+    I: Iterator<Item = String>,
+    N: FromStr,
+{
+    let value = value_for(option, args)?;
+    value.trim().parse().map_err(|_| invalid(option, &value))
+}
+
+// fail with a Conflict for the first of `others` that was given alongside `option`
+fn refuse(option: &str, others: &[(&str, bool)]) -> Result<(), CliError> {
+    match others.iter().find(|&&(_, given)| given) {
+        Some(&(other, _)) => Err(CliError::Conflict {
+            option: option.to_string(),
+            other: other.to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn invalid(option: &str, value: &str) -> CliError {
+    CliError::InvalidValue {
+        option: option.to_string(),
+        value: value.to_string(),
+    }
+}
+
+fn parse_bound<N: Number>(option: &str, value: &str) -> Result<N, CliError> {
+    match value.trim().parse::<N>() {
+        Ok(bound) if bound.is_finite() => Ok(bound),
+        _ => Err(invalid(option, value)),
+    }
+}
+
+// build the Config for one of the --number types from the --min/--max text (or the defaults)
+fn wide_config<N: Number>(
+    low: &Option<String>,
+    high: &Option<String>,
+    default_low: N,
+    default_high: N,
+    max_attempts: Option<u32>,
+) -> Result<Config<N>, CliError> {
+    let low = match *low {
+        Some(ref value) => parse_bound("--min", value)?,
+        None => default_low,
+    };
+    let high = match *high {
+        Some(ref value) => parse_bound("--max", value)?,
+        None => default_high,
+    };
+
+    Config::new(low, high, max_attempts).map_err(|e| CliError::InvalidRange(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Options, CliError> {
+        parse_args(line.split_whitespace().map(String::from))
+    }
+
+    fn conflict(option: &str, other: &str) -> Result<Options, CliError> {
+        Err(CliError::Conflict { option: option.to_string(), other: other.to_string() })
+    }
+
+    #[test]
+    fn no_options_is_the_normal_game() {
+        let options = parse("").unwrap();
+        assert_eq!(options.difficulty, Difficulty::Normal);
+        assert_eq!(options.config, Config::preset(Difficulty::Normal));
+        assert_eq!(options.locale, None);
+        assert!(options.stats.is_none());
+    }
+
+    #[test]
+    fn settings_on_top_of_a_preset_make_a_custom_game() {
+        let options = parse("-d hard --attempts 20").unwrap();
+        assert_eq!(options.difficulty, Difficulty::Custom);
+        assert_eq!(options.config, Config::new(1, 1000, Some(20)).unwrap());
+
+        // Ulam's game has no attempt limit unless one is asked for
+        assert_eq!(parse("--lies 2").unwrap().config.max_attempts, None);
+        assert_eq!(parse("--lies 2 --attempts 30").unwrap().config.max_attempts, Some(30));
+
+        assert_eq!(parse("--lang es_ES").unwrap().locale, Some(Locale::Spanish));
+    }
+
+    #[test]
+    fn stats_dates_include_the_whole_of_the_last_day() {
+        let filter = parse("stats --from 2024-03-01 --to 2024-03-01").unwrap().stats.unwrap();
+        assert_eq!(filter.until, filter.from.map(|from| from + 86_400));
+        assert_eq!(filter.difficulty, None);
+    }
+
+    #[test]
+    fn digits_and_lies_refuse_what_their_loops_cannot_do() {
+        for other in ["--tui", "--hints", "--record x", "--time-limit 30", "--speedrun 3", "--batch -"] {
+            let name = other.split(' ').next().unwrap();
+            assert_eq!(parse(&format!("--digits 4 {}", other)), conflict("--digits", name));
+            assert_eq!(parse(&format!("{} --lies 1", other)), conflict("--lies", name));
+        }
+        assert_eq!(parse("--digits 4 --lies 1"), conflict("--digits", "--lies"));
+        assert_eq!(parse("--digits 4 --auto"), conflict("--digits", "--auto"));
+        // the computer can play Ulam's game, though
+        assert!(parse("--lies 1 --auto").is_ok());
+    }
+
+    #[test]
+    fn other_conflicts() {
+        assert_eq!(parse("--tui --time-limit 30"), conflict("--time-limit", "--tui"));
+        assert_eq!(parse("--variant multi --hints"), conflict("--variant", "--hints"));
+        assert_eq!(parse("--variant shrink --auto"), conflict("--variant", "--auto"));
+        assert_eq!(parse("--number f64 --tui"), conflict("--number", "--tui"));
+    }
+
+    #[test]
+    fn unknown_and_missing_values() {
+        let bad = |option: &str, value: &str| Err(invalid(option, value));
+
+        assert_eq!(parse("--lang fr"), bad("--lang", "fr"));
+        assert_eq!(parse("--digits 11"), bad("--digits", "11"));
+        assert_eq!(parse("--digits 0"), bad("--digits", "0"));
+        assert_eq!(parse("--attempts many"), bad("--attempts", "many"));
+        assert_eq!(parse("--epsilon -1"), bad("--epsilon", "-1"));
+        assert_eq!(parse("--number u16"), bad("--number", "u16"));
+        assert_eq!(parse("stats --to 2024-13-01"), bad("--to", "2024-13-01"));
+        assert_eq!(parse("-d impossible"), bad("-d", "impossible"));
+
+        assert_eq!(parse("--lang"), Err(CliError::MissingValue(String::from("--lang"))));
+        assert_eq!(parse("--min 5 --max"), Err(CliError::MissingValue(String::from("--max"))));
+        assert_eq!(parse("--colour"), Err(CliError::UnknownOption(String::from("--colour"))));
+        assert!(matches!(parse("--min 10 --max 10"), Err(CliError::Config(_))));
+    }
+}
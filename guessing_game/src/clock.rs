@@ -0,0 +1,205 @@
+// time keeping for the timed modes
+// everything asks a Clock for the time instead of calling Instant::now() directly,
+// so tests can swap in a FakeClock and move time forward by hand
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+// a clock that never goes backwards
+// now() is the time since some fixed starting point - only the differences between readings matter
+pub trait Clock {
+    fn now(&self) -> Duration;
+}
+
+// the real clock, built on Instant (which is monotonic, unlike SystemTime)
+pub struct MonotonicClock {
+    start: Instant,
+}
+
+impl MonotonicClock {
+    pub fn new() -> MonotonicClock {
+        MonotonicClock { start: Instant::now() }
+    }
+}
+
+impl Default for MonotonicClock {
+    fn default() -> MonotonicClock {
+        MonotonicClock::new()
+    }
+}
+
+impl Clock for MonotonicClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+// a clock that only moves when told to
+// Cell lets advance() change the time through a shared & reference, just like a real clock changes by itself
+#[derive(Debug, Default)]
+pub struct FakeClock {
+    now: Cell<Duration>,
+}
+
+impl FakeClock {
+    pub fn new() -> FakeClock {
+        FakeClock::default()
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+// measures how long something took
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stopwatch {
+    started: Duration,
+}
+
+impl Stopwatch {
+    pub fn start(clock: &dyn Clock) -> Stopwatch {
+        Stopwatch { started: clock.now() }
+    }
+
+    pub fn elapsed(&self, clock: &dyn Clock) -> Duration {
+        // saturating_sub guards against a clock that was swapped for one reading an earlier time
+        clock.now().saturating_sub(self.started)
+    }
+}
+
+// a deadline counting down from a fixed limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Countdown {
+    stopwatch: Stopwatch,
+    limit: Duration,
+}
+
+impl Countdown {
+    pub fn start(clock: &dyn Clock, limit: Duration) -> Countdown {
+        Countdown {
+            stopwatch: Stopwatch::start(clock),
+            limit,
+        }
+    }
+
+    // how long is left, or zero once the deadline has passed
+    pub fn remaining(&self, clock: &dyn Clock) -> Duration {
+        self.limit.saturating_sub(self.stopwatch.elapsed(clock))
+    }
+
+    pub fn expired(&self, clock: &dyn Clock) -> bool {
+        self.stopwatch.elapsed(clock) >= self.limit
+    }
+}
+
+// the split times of a speed run, one per round
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Splits {
+    pub rounds: Vec<Duration>,
+}
+
+impl Splits {
+    pub fn push(&mut self, time: Duration) {
+        self.rounds.push(time);
+    }
+
+    pub fn total(&self) -> Duration {
+        self.rounds.iter().sum()
+    }
+
+    pub fn fastest(&self) -> Option<Duration> {
+        self.rounds.iter().cloned().min()
+    }
+
+    pub fn slowest(&self) -> Option<Duration> {
+        self.rounds.iter().cloned().max()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(n: u64) -> Duration {
+        Duration::from_secs(n)
+    }
+
+    #[test]
+    fn fake_clock_only_moves_when_advanced() {
+        let clock = FakeClock::new();
+        assert_eq!(clock.now(), Duration::ZERO);
+
+        clock.advance(secs(3));
+        clock.advance(Duration::from_millis(500));
+
+        assert_eq!(clock.now(), Duration::from_millis(3500));
+    }
+
+    #[test]
+    fn stopwatch_measures_from_when_it_started() {
+        let clock = FakeClock::new();
+        clock.advance(secs(10));
+        let stopwatch = Stopwatch::start(&clock);
+
+        assert_eq!(stopwatch.elapsed(&clock), Duration::ZERO);
+        clock.advance(secs(4));
+        assert_eq!(stopwatch.elapsed(&clock), secs(4));
+    }
+
+    #[test]
+    fn countdown_runs_out_at_the_deadline() {
+        let clock = FakeClock::new();
+        let countdown = Countdown::start(&clock, secs(30));
+
+        clock.advance(secs(29));
+        assert!(!countdown.expired(&clock));
+        assert_eq!(countdown.remaining(&clock), secs(1));
+
+        clock.advance(secs(1));
+        assert!(countdown.expired(&clock));
+        assert_eq!(countdown.remaining(&clock), Duration::ZERO);
+    }
+
+    #[test]
+    fn countdown_remaining_stays_at_zero_past_the_deadline() {
+        let clock = FakeClock::new();
+        let countdown = Countdown::start(&clock, secs(5));
+
+        clock.advance(secs(60));
+
+        assert!(countdown.expired(&clock));
+        assert_eq!(countdown.remaining(&clock), Duration::ZERO);
+    }
+
+    #[test]
+    fn splits_add_up_a_speed_run() {
+        let clock = FakeClock::new();
+        let mut splits = Splits::default();
+
+        for round in &[secs(12), secs(7), secs(20)] {
+            let stopwatch = Stopwatch::start(&clock);
+            clock.advance(*round);
+            splits.push(stopwatch.elapsed(&clock));
+        }
+
+        assert_eq!(splits.rounds, vec![secs(12), secs(7), secs(20)]);
+        assert_eq!(splits.total(), secs(39));
+        assert_eq!(splits.fastest(), Some(secs(7)));
+        assert_eq!(splits.slowest(), Some(secs(20)));
+    }
+
+    #[test]
+    fn an_empty_speed_run_has_no_fastest_or_slowest() {
+        let splits = Splits::default();
+
+        assert_eq!(splits.total(), Duration::ZERO);
+        assert_eq!(splits.fastest(), None);
+        assert_eq!(splits.slowest(), None);
+    }
+}
@@ -0,0 +1,166 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::number::Number;
+
+// the preset difficulty levels
+// Custom is used whenever the player sets their own range or attempt limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    Custom,
+}
+
+impl Difficulty {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Custom => "custom",
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// implementing FromStr means we can write "hard".parse::<Difficulty>() just like we parse numbers
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Difficulty, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            "custom" => Ok(Difficulty::Custom),
+            _ => Err(format!("unknown difficulty '{}' (expected easy, normal, hard or custom)", s)),
+        }
+    }
+}
+
+// the rules for one round: which numbers the secret can be and how many guesses the player gets
+// deriving Copy is fine here because the struct only holds numbers
+// like Game, plain Config means Config<u32>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config<T = u32> {
+    // the range is inclusive at both ends: low..=high
+    pub low: T,
+    pub high: T,
+    // None means the player can keep guessing forever
+    pub max_attempts: Option<u32>,
+}
+
+// the ways a Config can be invalid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError<T = u32> {
+    // low and high are the same number, so there is nothing to guess
+    EmptyRange { value: T },
+    // low is bigger than high
+    InvertedRange { low: T, high: T },
+    // a limit of 0 attempts would lose before the first guess
+    NoAttempts,
+}
+
+impl<T: fmt::Display> fmt::Display for ConfigError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConfigError::EmptyRange { ref value } => {
+                write!(f, "the range {}..{} only contains one number", value, value)
+            }
+            ConfigError::InvertedRange { ref low, ref high } => {
+                write!(f, "the lower bound {} is bigger than the upper bound {}", low, high)
+            }
+            ConfigError::NoAttempts => write!(f, "the player needs at least one attempt"),
+        }
+    }
+}
+
+impl<T: Number> Config<T> {
+    // Associated function - checks the values before handing back a Config
+    // returning a Result forces the caller to deal with a bad range instead of crashing later
+    pub fn new(low: T, high: T, max_attempts: Option<u32>) -> Result<Config<T>, ConfigError<T>> {
+        if low > high {
+            return Err(ConfigError::InvertedRange { low, high });
+        }
+        if low == high {
+            return Err(ConfigError::EmptyRange { value: low });
+        }
+        if max_attempts == Some(0) {
+            return Err(ConfigError::NoAttempts);
+        }
+
+        Ok(Config { low, high, max_attempts })
+    }
+}
+
+impl Config<u32> {
+    // the preset values for each difficulty
+    // Custom starts from the normal rules and is expected to be changed by the caller
+    pub fn preset(difficulty: Difficulty) -> Config {
+        match difficulty {
+            Difficulty::Easy => Config { low: 1, high: 50, max_attempts: Some(10) },
+            Difficulty::Normal | Difficulty::Custom => Config { low: 1, high: 100, max_attempts: Some(10) },
+            Difficulty::Hard => Config { low: 1, high: 1000, max_attempts: Some(10) },
+        }
+    }
+}
+
+// the original game: 1 to 100
+impl Default for Config {
+    fn default() -> Config {
+        Config::preset(Difficulty::Normal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_accepts_a_valid_range() {
+        assert_eq!(
+            Config::new(1u32, 10, Some(3)),
+            Ok(Config { low: 1, high: 10, max_attempts: Some(3) })
+        );
+        assert_eq!(Config::new(1u32, 10, None).map(|c| c.max_attempts), Ok(None));
+    }
+
+    #[test]
+    fn new_rejects_a_range_of_one_number() {
+        assert_eq!(Config::new(5u32, 5, None), Err(ConfigError::EmptyRange { value: 5 }));
+    }
+
+    #[test]
+    fn new_rejects_an_inverted_range() {
+        assert_eq!(Config::new(10u32, 1, None), Err(ConfigError::InvertedRange { low: 10, high: 1 }));
+    }
+
+    #[test]
+    fn new_rejects_zero_attempts() {
+        assert_eq!(Config::new(1u32, 10, Some(0)), Err(ConfigError::NoAttempts));
+    }
+
+    #[test]
+    fn presets_match_each_difficulty() {
+        assert_eq!(Config::preset(Difficulty::Easy), Config { low: 1, high: 50, max_attempts: Some(10) });
+        assert_eq!(Config::preset(Difficulty::Normal), Config { low: 1, high: 100, max_attempts: Some(10) });
+        assert_eq!(Config::preset(Difficulty::Hard), Config { low: 1, high: 1000, max_attempts: Some(10) });
+        assert_eq!(Config::default(), Config::preset(Difficulty::Normal));
+    }
+
+    #[test]
+    fn difficulty_names_parse_back() {
+        for &difficulty in &[Difficulty::Easy, Difficulty::Normal, Difficulty::Hard, Difficulty::Custom] {
+            assert_eq!(difficulty.name().parse(), Ok(difficulty));
+        }
+        assert_eq!("HARD".parse(), Ok(Difficulty::Hard));
+        assert!("impossible".parse::<Difficulty>().is_err());
+    }
+}
@@ -0,0 +1,287 @@
+use std::fmt;
+// the Ordering enum allows us to compare numbers using the Less, Equal, and Greater variants
+use std::cmp::Ordering;
+// the Rng 'trait' defines methods that random number generators implement
+// this trait MUST BE IN SCOPE for us to use those methods
+use rand::Rng;
+// Uniform::new_inclusive lets us pick from low..=high without adding 1 to high (which could overflow)
+use rand::distributions::Uniform;
+
+use crate::config::Config;
+use crate::number::Number;
+
+// the result of comparing a single guess against the secret number
+// this is our own version of Ordering that reads nicely from the player's point of view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    TooSmall,
+    Correct,
+    TooLarge,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match *self {
+            Outcome::TooSmall => "too small",
+            Outcome::Correct => "correct",
+            Outcome::TooLarge => "too large",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+impl Outcome {
+    // the name used in JSON output (batch mode and the HTTP API), which never has spaces in it
+    pub fn as_json_str(self) -> &'static str {
+        match self {
+            Outcome::TooSmall => "too_small",
+            Outcome::Correct => "correct",
+            Outcome::TooLarge => "too_large",
+        }
+    }
+}
+
+// one entry in the guess history - the number that was guessed and what the game said about it
+// T = u32 is a 'default type parameter': plain Attempt means Attempt<u32>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Attempt<T = u32> {
+    pub guess: T,
+    pub outcome: Outcome,
+}
+
+// Game holds all of the state for one round, but does no input or output itself
+// the front-end reads a guess, hands it to guess() and decides what to print from the Outcome
+// it works with any Number type - plain Game is the original u32 game
+#[derive(Debug)]
+pub struct Game<T = u32> {
+    secret: T,
+    config: Config<T>,
+    // how close a guess has to be to count as correct (zero for whole numbers)
+    tolerance: T,
+    history: Vec<Attempt<T>>,
+}
+
+impl<T: Number> Game<T> {
+    // Associated function - creates a game with a known secret number
+    // this is what tests use so that the answer is not random
+    pub fn new(secret: T, config: Config<T>) -> Game<T> {
+        Game {
+            secret,
+            config,
+            tolerance: T::zero(),
+            history: Vec::new(),
+        }
+    }
+
+    // creates a game with a random secret number anywhere in the configured range
+    // the caller passes in the random number generator, so a seeded one gives a repeatable game
+    // ?Sized allows rng to be a trait object such as &mut dyn RngCore
+    pub fn random<R: Rng + ?Sized>(config: Config<T>, rng: &mut R) -> Game<T> {
+        let secret = rng.sample(Uniform::new_inclusive(config.low, config.high));
+        Game::new(secret, config)
+    }
+
+    // accept guesses within `tolerance` of the secret - used for decimal games, where an exact match is unlikely
+    pub fn with_tolerance(mut self, tolerance: T) -> Game<T> {
+        self.tolerance = tolerance;
+        self
+    }
+
+    // compare a guess against the secret number and record it in the history
+    pub fn guess(&mut self, guess: T) -> Outcome {
+        // we can use the 'match' keyword to compare variables with the compare function
+        // each 'arm' of the match expression maps one Ordering variant onto an Outcome
+        let outcome = match T::compare(guess, self.secret, self.tolerance) {
+            Ordering::Less => Outcome::TooSmall,
+            Ordering::Equal => Outcome::Correct,
+            Ordering::Greater => Outcome::TooLarge,
+        };
+
+        self.history.push(Attempt { guess, outcome });
+
+        outcome
+    }
+
+    pub fn secret(&self) -> T {
+        self.secret
+    }
+
+    pub fn config(&self) -> &Config<T> {
+        &self.config
+    }
+
+    pub fn tolerance(&self) -> T {
+        self.tolerance
+    }
+
+    pub fn low(&self) -> T {
+        self.config.low
+    }
+
+    pub fn high(&self) -> T {
+        self.config.high
+    }
+
+    // how many guesses have been made so far
+    pub fn attempts(&self) -> u32 {
+        self.history.len() as u32
+    }
+
+    // every guess made so far, oldest first
+    // returning a slice (&[Attempt]) lets callers read the history without being able to change it
+    pub fn history(&self) -> &[Attempt<T>] {
+        &self.history
+    }
+
+    // the round is won once any guess has been Correct
+    pub fn is_won(&self) -> bool {
+        self.history.iter().any(|attempt| attempt.outcome == Outcome::Correct)
+    }
+
+    // how many guesses the player has left, or None when there is no limit
+    pub fn attempts_left(&self) -> Option<u32> {
+        self.config
+            .max_attempts
+            .map(|max| max.saturating_sub(self.attempts()))
+    }
+
+    // the round is lost when every allowed attempt has been used without finding the number
+    pub fn is_lost(&self) -> bool {
+        !self.is_won() && self.attempts_left() == Some(0)
+    }
+
+    // once a round is over the front-end should stop asking for guesses
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.is_lost()
+    }
+}
+
+// methods that only make sense for the original whole-number game
+impl Game<u32> {
+
+    // the part of the range that is still possible given every answer so far
+    // returns (lowest, highest) - both inclusive
+    pub fn narrowed_range(&self) -> (u32, u32) {
+        let mut low = self.config.low;
+        let mut high = self.config.high;
+
+        for attempt in &self.history {
+            match attempt.outcome {
+                Outcome::TooSmall => low = low.max(attempt.guess.saturating_add(1)),
+                Outcome::TooLarge => high = high.min(attempt.guess.saturating_sub(1)),
+                Outcome::Correct => {
+                    low = attempt.guess;
+                    high = attempt.guess;
+                }
+            }
+        }
+
+        (low, high)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a known secret and a limit of three guesses
+    fn game(secret: u32) -> Game {
+        Game::new(secret, Config::new(1, 100, Some(3)).unwrap())
+    }
+
+    #[test]
+    fn guess_maps_each_ordering_onto_an_outcome() {
+        let mut game = game(42);
+
+        assert_eq!(game.guess(10), Outcome::TooSmall);
+        assert_eq!(game.guess(90), Outcome::TooLarge);
+        assert_eq!(game.guess(42), Outcome::Correct);
+    }
+
+    #[test]
+    fn guesses_are_recorded_in_order() {
+        let mut game = game(42);
+        game.guess(10);
+        game.guess(42);
+
+        assert_eq!(game.attempts(), 2);
+        assert_eq!(
+            game.history(),
+            &[
+                Attempt { guess: 10, outcome: Outcome::TooSmall },
+                Attempt { guess: 42, outcome: Outcome::Correct },
+            ]
+        );
+    }
+
+    #[test]
+    fn a_correct_guess_wins() {
+        let mut game = game(42);
+        assert!(!game.is_over());
+
+        game.guess(42);
+
+        assert!(game.is_won());
+        assert!(!game.is_lost());
+        assert!(game.is_over());
+    }
+
+    #[test]
+    fn using_every_attempt_without_finding_it_loses() {
+        let mut game = game(42);
+
+        game.guess(1);
+        assert_eq!(game.attempts_left(), Some(2));
+        game.guess(2);
+        assert!(!game.is_lost());
+        game.guess(3);
+
+        assert_eq!(game.attempts_left(), Some(0));
+        assert!(game.is_lost());
+        assert!(game.is_over());
+    }
+
+    #[test]
+    fn winning_on_the_last_attempt_is_not_a_loss() {
+        let mut game = game(42);
+        game.guess(1);
+        game.guess(2);
+        game.guess(42);
+
+        assert!(game.is_won());
+        assert!(!game.is_lost());
+    }
+
+    #[test]
+    fn no_limit_means_no_attempts_left_count() {
+        let mut game: Game = Game::new(42, Config::new(1, 100, None).unwrap());
+        for guess in 1..42 {
+            game.guess(guess);
+        }
+
+        assert_eq!(game.attempts_left(), None);
+        assert!(!game.is_lost());
+    }
+
+    #[test]
+    fn narrowed_range_follows_the_answers() {
+        let mut game = game(42);
+        assert_eq!(game.narrowed_range(), (1, 100));
+
+        game.guess(30);
+        assert_eq!(game.narrowed_range(), (31, 100));
+        game.guess(50);
+        assert_eq!(game.narrowed_range(), (31, 49));
+        game.guess(42);
+        assert_eq!(game.narrowed_range(), (42, 42));
+    }
+
+    #[test]
+    fn tolerance_accepts_close_decimal_guesses() {
+        let config = Config::new(0.0, 1.0, None).unwrap();
+        let mut game = Game::new(0.5, config).with_tolerance(0.01);
+
+        assert_eq!(game.guess(0.4), Outcome::TooSmall);
+        assert_eq!(game.guess(0.505), Outcome::Correct);
+    }
+}
@@ -0,0 +1,224 @@
+// hot/cold hints: how close a guess was, on top of the usual Too small / Too large
+use std::fmt;
+use std::str::FromStr;
+
+use crate::game::Game;
+
+// how close a guess was, as a share of the whole range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Band {
+    Freezing,
+    Cold,
+    Warm,
+    Hot,
+}
+
+impl fmt::Display for Band {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match *self {
+            Band::Freezing => "Freezing",
+            Band::Cold => "Cold",
+            Band::Warm => "Warm",
+            Band::Hot => "Hot",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+// whether a guess was closer to the secret than the one before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Warmer,
+    Colder,
+    Same,
+}
+
+impl fmt::Display for Trend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match *self {
+            Trend::Warmer => "warmer than last time",
+            Trend::Colder => "colder than last time",
+            Trend::Same => "no closer than last time",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+// where the bands start, as fractions of the range size
+// a guess within `hot` of the secret is Hot, within `warm` is Warm, within `cold` is Cold and anything further is Freezing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Thresholds {
+    pub hot: f64,
+    pub warm: f64,
+    pub cold: f64,
+}
+
+impl Thresholds {
+    // the bands must grow from hot to cold and stay inside the range
+    pub fn new(hot: f64, warm: f64, cold: f64) -> Result<Thresholds, String> {
+        if !(0.0 < hot && hot <= warm && warm <= cold && cold <= 1.0) {
+            return Err(format!(
+                "hint bands must satisfy 0 < hot <= warm <= cold <= 1 (got {}, {}, {})",
+                hot, warm, cold
+            ));
+        }
+
+        Ok(Thresholds { hot, warm, cold })
+    }
+
+    // which band a guess falls into
+    pub fn band(&self, distance: u32, range_size: u32) -> Band {
+        // max(1) avoids dividing by zero for a range of one number
+        let share = f64::from(distance) / f64::from(range_size.max(1));
+
+        if share <= self.hot {
+            Band::Hot
+        } else if share <= self.warm {
+            Band::Warm
+        } else if share <= self.cold {
+            Band::Cold
+        } else {
+            Band::Freezing
+        }
+    }
+}
+
+impl Default for Thresholds {
+    fn default() -> Thresholds {
+        Thresholds { hot: 0.05, warm: 0.15, cold: 0.35 }
+    }
+}
+
+// parses "HOT,WARM,COLD", for example "0.05,0.15,0.35"
+impl FromStr for Thresholds {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Thresholds, String> {
+        let parts: Result<Vec<f64>, _> = s.split(',').map(|part| part.trim().parse::<f64>()).collect();
+
+        match parts {
+            Ok(ref values) if values.len() == 3 => Thresholds::new(values[0], values[1], values[2]),
+            _ => Err(format!("'{}' should be three numbers like 0.05,0.15,0.35", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hint {
+    pub band: Band,
+    // None for the first guess, because there is nothing to compare it with
+    pub trend: Option<Trend>,
+}
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.trend {
+            Some(trend) => write!(f, "{} - {}.", self.band, trend),
+            None => write!(f, "{}.", self.band),
+        }
+    }
+}
+
+// the hint for the most recent guess in a game, or None if nothing has been guessed yet
+pub fn for_last_guess(game: &Game, thresholds: &Thresholds) -> Option<Hint> {
+    let secret = game.secret();
+    // abs_diff gives the gap between two unsigned numbers without risking a negative result
+    let distance = |guess: u32| guess.abs_diff(secret);
+
+    // iterate from the newest guess backwards to get the last two
+    let mut recent = game.history().iter().rev();
+    let last = distance(recent.next()?.guess);
+    let previous = recent.next().map(|attempt| distance(attempt.guess));
+
+    let trend = previous.map(|previous| {
+        if last < previous {
+            Trend::Warmer
+        } else if last > previous {
+            Trend::Colder
+        } else {
+            Trend::Same
+        }
+    });
+
+    Some(Hint {
+        band: thresholds.band(last, game.high() - game.low()),
+        trend,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    // bands from coldest to hottest, so that "no colder" can be checked with <=
+    fn heat(band: Band) -> u32 {
+        match band {
+            Band::Freezing => 0,
+            Band::Cold => 1,
+            Band::Warm => 2,
+            Band::Hot => 3,
+        }
+    }
+
+    #[test]
+    fn every_hint_is_true_for_the_secret() {
+        let config = Config::new(1, 40, None).unwrap();
+        let thresholds = Thresholds::default();
+
+        for secret in 1..=40 {
+            for previous in (1..=40).filter(|&guess| guess != secret) {
+                for last in 1..=40 {
+                    let mut game = Game::new(secret, config);
+                    game.guess(previous);
+                    game.guess(last);
+                    let hint = for_last_guess(&game, &thresholds).unwrap();
+
+                    let (near, far) = (last.abs_diff(secret), previous.abs_diff(secret));
+                    let trend = match near.cmp(&far) {
+                        std::cmp::Ordering::Less => Trend::Warmer,
+                        std::cmp::Ordering::Greater => Trend::Colder,
+                        std::cmp::Ordering::Equal => Trend::Same,
+                    };
+                    assert_eq!(hint.trend, Some(trend), "secret {}, guesses {} then {}", secret, previous, last);
+                    // the band only depends on how far away the guess was
+                    assert_eq!(hint.band, thresholds.band(near, 39));
+                    if near == 0 {
+                        assert_eq!(hint.band, Band::Hot);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn closer_is_never_colder() {
+        let thresholds = Thresholds::default();
+        let bands: Vec<Band> = (0..=1000).map(|distance| thresholds.band(distance, 1000)).collect();
+
+        assert!(bands.windows(2).all(|pair| heat(pair[0]) >= heat(pair[1])));
+        assert_eq!((bands[0], bands[50], bands[51]), (Band::Hot, Band::Hot, Band::Warm));
+        assert_eq!((bands[150], bands[350], bands[351]), (Band::Warm, Band::Cold, Band::Freezing));
+        // a range of one number has nothing to divide by
+        assert_eq!(thresholds.band(0, 0), Band::Hot);
+    }
+
+    #[test]
+    fn no_hint_before_the_first_guess_and_no_trend_after_it() {
+        let mut game = Game::new(50, Config::new(1, 100, None).unwrap());
+        assert_eq!(for_last_guess(&game, &Thresholds::default()), None);
+
+        game.guess(100);
+        assert_eq!(for_last_guess(&game, &Thresholds::default()), Some(Hint { band: Band::Freezing, trend: None }));
+    }
+
+    #[test]
+    fn bands_have_to_grow_from_hot_to_cold() {
+        assert_eq!("0.1, 0.2,0.5".parse(), Ok(Thresholds { hot: 0.1, warm: 0.2, cold: 0.5 }));
+        assert!("0.2,0.1,0.5".parse::<Thresholds>().is_err());
+        assert!("0,0.1,0.5".parse::<Thresholds>().is_err());
+        assert!("0.1,0.2,1.5".parse::<Thresholds>().is_err());
+        assert!("0.1,0.2".parse::<Thresholds>().is_err());
+        assert!("hot,warm,cold".parse::<Thresholds>().is_err());
+    }
+}
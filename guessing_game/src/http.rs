@@ -0,0 +1,441 @@
+// a small HTTP/JSON API for playing the game from other programs on the same machine
+//
+//   POST /sessions               start a game - the body may set {"low": 1, "high": 100, "attempts": 10, "seed": 42}
+//                                ("attempts": null means unlimited guesses)
+//   POST /sessions/<id>/guesses  make a guess - the body is {"guess": 50}
+//   GET  /sessions/<id>          how the game is going
+//
+// every reply is a JSON object, errors look like {"error": "..."}
+// a session that nobody has used for the timeout is forgotten, and asking for it again gives 404
+//
+// the server only speaks enough HTTP/1.1 for this: one request per connection, bodies sized by Content-Length
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::batch::json_string;
+use crate::clock::{Clock, MonotonicClock};
+use crate::config::Config;
+use crate::game::{Game, Outcome};
+use crate::input::InputError;
+
+// how long a session is kept without any requests, unless --session-timeout says otherwise
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
+
+// requests are tiny - anything bigger than this is refused rather than read into memory
+const MAX_BODY: usize = 4096;
+// the same for the request line and the headers together
+const MAX_HEADER: usize = 8192;
+
+// a client that stops sending half way through a request gets dropped after this long
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    // without any ?query part
+    pub path: String,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    // always a JSON object
+    pub body: String,
+}
+
+impl Response {
+    fn json(status: u16, body: String) -> Response {
+        Response { status, body }
+    }
+
+    fn error(status: u16, message: &str) -> Response {
+        Response::json(status, format!("{{\"error\":{}}}", json_string(message)))
+    }
+
+    // write the whole response, headers and all
+    pub fn write_to<W: Write>(&self, mut out: W) -> io::Result<()> {
+        write!(
+            out,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            reason(self.status),
+            self.body.len(),
+            self.body
+        )?;
+        out.flush()
+    }
+}
+
+// the text that goes after the status code on the first line of a response
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        _ => "Unknown",
+    }
+}
+
+// one game and when it was last touched
+struct Slot {
+    game: Game,
+    last_used: Duration,
+}
+
+// every live game, by id
+// the methods take the current time as a Duration from a Clock, so expiry can be checked without waiting
+pub struct Sessions {
+    slots: HashMap<u64, Slot>,
+    next_id: u64,
+    timeout: Duration,
+    // where the secret numbers come from when a session is started without a seed
+    rng: ChaCha8Rng,
+}
+
+impl Sessions {
+    pub fn new(timeout: Duration) -> Sessions {
+        Sessions {
+            slots: HashMap::new(),
+            next_id: 1,
+            timeout,
+            rng: ChaCha8Rng::from_entropy(),
+        }
+    }
+
+    // how many sessions are still alive
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    // forget every session that has not been used for longer than the timeout
+    pub fn expire(&mut self, now: Duration) {
+        let timeout = self.timeout;
+        // retain keeps only the entries the closure returns true for
+        self.slots.retain(|_, slot| now.saturating_sub(slot.last_used) <= timeout);
+    }
+
+    // answer one request
+    pub fn handle(&mut self, request: &Request, now: Duration) -> Response {
+        self.expire(now);
+
+        let segments: Vec<&str> = request.path.split('/').filter(|s| !s.is_empty()).collect();
+
+        match (request.method.as_str(), segments.as_slice()) {
+            ("POST", ["sessions"]) => self.create(&request.body, now),
+            ("GET", ["sessions", id]) => self.state(id, now),
+            ("POST", ["sessions", id, "guesses"]) => self.guess(id, &request.body, now),
+            (_, ["sessions"]) | (_, ["sessions", _]) | (_, ["sessions", _, "guesses"]) => {
+                Response::error(405, "method not allowed")
+            }
+            _ => Response::error(404, "no such page"),
+        }
+    }
+
+    fn create(&mut self, body: &str, now: Duration) -> Response {
+        let (config, seed) = match parse_object(body).and_then(|fields| new_game_settings(&fields)) {
+            Ok(settings) => settings,
+            Err(e) => return Response::error(400, &e),
+        };
+
+        // a seeded session gets its own generator so the same seed always gives the same secret
+        let game = match seed {
+            Some(seed) => Game::random(config, &mut ChaCha8Rng::seed_from_u64(seed)),
+            None => Game::random(config, &mut self.rng),
+        };
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.slots.insert(id, Slot { game, last_used: now });
+
+        Response::json(201, state_json(id, &self.slots[&id].game, None))
+    }
+
+    fn state(&mut self, id: &str, now: Duration) -> Response {
+        match self.slot(id, now) {
+            Some((id, slot)) => Response::json(200, state_json(id, &slot.game, None)),
+            None => Response::error(404, "no such session (it may have expired)"),
+        }
+    }
+
+    fn guess(&mut self, id: &str, body: &str, now: Duration) -> Response {
+        let (id, slot) = match self.slot(id, now) {
+            Some(found) => found,
+            None => return Response::error(404, "no such session (it may have expired)"),
+        };
+        let game = &mut slot.game;
+
+        let guess = match parse_object(body).map(|fields| fields.get("guess").cloned()) {
+            Ok(Some(Value::Number(n))) => n,
+            Ok(_) => return Response::error(400, "the body must be {\"guess\": <whole number>}"),
+            Err(e) => return Response::error(400, &e),
+        };
+
+        if game.is_over() {
+            return Response::error(409, "the game is already over");
+        }
+
+        if guess < u64::from(game.low()) || guess > u64::from(game.high()) {
+            let error = InputError::OutOfRange {
+                low: game.low().to_string(),
+                high: game.high().to_string(),
+            };
+            return Response::error(400, &error.to_string());
+        }
+
+        let outcome = game.guess(guess as u32);
+        Response::json(200, state_json(id, game, Some(outcome)))
+    }
+
+    // look up a session by the id in the path, marking it as used
+    fn slot(&mut self, id: &str, now: Duration) -> Option<(u64, &mut Slot)> {
+        let id: u64 = id.parse().ok()?;
+        let slot = self.slots.get_mut(&id)?;
+        slot.last_used = now;
+        Some((id, slot))
+    }
+}
+
+// the JSON for a session - the secret is only included once the game is over
+// after a guess the outcome of that guess comes first
+fn state_json(id: u64, game: &Game, outcome: Option<Outcome>) -> String {
+    let mut json = String::from("{");
+    if let Some(outcome) = outcome {
+        json.push_str(&format!("\"outcome\":\"{}\",", outcome.as_json_str()));
+    }
+
+    let status = if game.is_won() {
+        "won"
+    } else if game.is_lost() {
+        "lost"
+    } else {
+        "playing"
+    };
+
+    json.push_str(&format!(
+        "\"id\":{},\"status\":\"{}\",\"low\":{},\"high\":{},\"attempts\":{},\"max_attempts\":{},\"attempts_left\":{}",
+        id,
+        status,
+        game.low(),
+        game.high(),
+        game.attempts(),
+        json_option(game.config().max_attempts),
+        json_option(game.attempts_left())
+    ));
+
+    let history: Vec<String> = game
+        .history()
+        .iter()
+        .map(|attempt| format!("{{\"guess\":{},\"outcome\":\"{}\"}}", attempt.guess, attempt.outcome.as_json_str()))
+        .collect();
+    json.push_str(&format!(",\"history\":[{}]", history.join(",")));
+
+    if game.is_over() {
+        json.push_str(&format!(",\"secret\":{}", game.secret()));
+    }
+
+    json.push('}');
+    json
+}
+
+fn json_option(value: Option<u32>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => String::from("null"),
+    }
+}
+
+// the game config and seed asked for when creating a session - anything left out comes from Config::default()
+fn new_game_settings(fields: &HashMap<String, Value>) -> Result<(Config, Option<u64>), String> {
+    let defaults = Config::default();
+    let mut low = defaults.low;
+    let mut high = defaults.high;
+    let mut max_attempts = defaults.max_attempts;
+    let mut seed = None;
+
+    for (name, &value) in fields {
+        match (name.as_str(), value) {
+            ("low", Value::Number(n)) => low = to_u32(name, n)?,
+            ("high", Value::Number(n)) => high = to_u32(name, n)?,
+            ("attempts", Value::Number(n)) => max_attempts = Some(to_u32(name, n)?),
+            ("attempts", Value::Null) => max_attempts = None,
+            ("seed", Value::Number(n)) => seed = Some(n),
+            ("seed", Value::Null) => seed = None,
+            ("low", _) | ("high", _) => return Err(format!("'{}' must be a whole number", name)),
+            _ => return Err(format!("unknown field '{}'", name)),
+        }
+    }
+
+    let config = Config::new(low, high, max_attempts).map_err(|e| e.to_string())?;
+    Ok((config, seed))
+}
+
+fn to_u32(name: &str, value: u64) -> Result<u32, String> {
+    if value > u64::from(u32::MAX) {
+        Err(format!("'{}' is too big", name))
+    } else {
+        Ok(value as u32)
+    }
+}
+
+// the only JSON values the API needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Value {
+    Number(u64),
+    Null,
+}
+
+// read a flat JSON object whose values are whole numbers or null, e.g. {"low": 1, "high": 100}
+// an empty body counts as {}
+fn parse_object(body: &str) -> Result<HashMap<String, Value>, String> {
+    let mut fields = HashMap::new();
+    let body = body.trim();
+    if body.is_empty() {
+        return Ok(fields);
+    }
+
+    let inner = body
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+        .ok_or_else(|| String::from("the body must be a JSON object"))?;
+    if inner.trim().is_empty() {
+        return Ok(fields);
+    }
+
+    // the values are never strings, so a comma or colon can only ever be a separator
+    for pair in inner.split(',') {
+        let mut parts = pair.splitn(2, ':');
+        let key = parts.next().unwrap_or("").trim();
+        let value = parts.next().ok_or_else(|| format!("expected \"name\": value, found '{}'", pair.trim()))?.trim();
+
+        let key = key
+            .strip_prefix('"')
+            .and_then(|rest| rest.strip_suffix('"'))
+            .ok_or_else(|| format!("expected a quoted field name, found '{}'", key))?;
+
+        let value = if value == "null" {
+            Value::Null
+        } else {
+            let n = value.parse().map_err(|_| format!("'{}' must be a whole number, found '{}'", key, value))?;
+            Value::Number(n)
+        };
+
+        fields.insert(key.to_string(), value);
+    }
+
+    Ok(fields)
+}
+
+// read one request: the request line, the headers, then Content-Length bytes of body
+// a request we can't make sense of comes back as an InvalidData error
+pub fn read_request<R: BufRead>(reader: &mut R) -> io::Result<Request> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+
+    // how many more bytes of request line and headers we are willing to read
+    let mut budget = MAX_HEADER;
+
+    let line = read_header_line(reader, &mut budget)?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().ok_or_else(|| invalid("empty request"))?.to_string();
+    let target = parts.next().ok_or_else(|| invalid("no path in the request line"))?;
+    let path = target.split('?').next().unwrap_or("").to_string();
+
+    let mut length = 0;
+    loop {
+        let header = read_header_line(reader, &mut budget)?;
+        if header.is_empty() {
+            return Err(invalid("the headers never finished"));
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().map_err(|_| invalid("bad Content-Length"))?;
+            }
+        }
+    }
+
+    if length > MAX_BODY {
+        return Err(invalid("the body is too big"));
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    let body = String::from_utf8(body).map_err(|_| invalid("the body is not UTF-8"))?;
+
+    Ok(Request { method, path, body })
+}
+
+// read one line of the request, taking its length off the budget
+// take() stops read_line at the budget, so a client can't make us buffer an endless line
+fn read_header_line<R: BufRead>(reader: &mut R, budget: &mut usize) -> io::Result<String> {
+    let mut line = String::new();
+    let read = reader.by_ref().take(*budget as u64).read_line(&mut line)?;
+    *budget -= read;
+
+    if *budget == 0 && !line.ends_with('\n') {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "the headers are too big"));
+    }
+    Ok(line)
+}
+
+// the API only listens on loopback addresses, just like the multiplayer server
+pub use crate::server::bind;
+
+// answer requests on the listener until the process is stopped, one thread per connection
+pub fn serve(listener: TcpListener, timeout: Duration) -> io::Result<()> {
+    let sessions = Arc::new(Mutex::new(Sessions::new(timeout)));
+    let clock = Arc::new(MonotonicClock::new());
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            // one failed connection should not bring the whole server down
+            Err(_) => continue,
+        };
+
+        let sessions = Arc::clone(&sessions);
+        let clock = Arc::clone(&clock);
+        // a client that hangs up early is its own problem, so the result is ignored
+        thread::spawn(move || {
+            let _ = handle_connection(stream, &sessions, clock.as_ref());
+        });
+    }
+
+    Ok(())
+}
+
+// a poisoned Mutex means another connection's thread panicked - the sessions are still usable
+fn lock(sessions: &Mutex<Sessions>) -> MutexGuard<'_, Sessions> {
+    sessions.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn handle_connection(stream: TcpStream, sessions: &Mutex<Sessions>, clock: &dyn Clock) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let response = match read_request(&mut reader) {
+        Ok(request) => lock(sessions).handle(&request, clock.now()),
+        Err(ref e) if e.kind() == io::ErrorKind::InvalidData => Response::error(400, &e.to_string()),
+        Err(e) => return Err(e),
+    };
+
+    response.write_to(&stream)
+}
@@ -0,0 +1,191 @@
+use std::fmt;
+
+use crate::game::Outcome;
+use crate::number::Number;
+
+// everything the player can type at the "Enter your guess" prompt
+// plain Command is a guess at the original u32 game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command<T = u32> {
+    Guess(T),
+    Quit,
+}
+
+// the different ways a line of input can fail to be a guess
+// each variant gets its own message so the player knows what to fix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputError {
+    Empty,
+    Negative,
+    // the bounds are kept as text so that one error type works for every kind of number
+    OutOfRange { low: String, high: String },
+    Overflow,
+    NotANumber(String),
+    // an answer to the computer's guess that is not +, - or =
+    NotAnAnswer,
+    // an answer that no number could give, after the answers before it
+    Contradiction,
+}
+
+// implementing the Display trait lets us print an InputError with {} in println!
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            InputError::Empty => write!(f, "Please type a number (or 'quit' to give up)."),
+            InputError::Negative => write!(f, "The secret number is never negative."),
+            InputError::OutOfRange { ref low, ref high } => {
+                write!(f, "Your guess must be between {} and {}.", low, high)
+            }
+            InputError::Overflow => write!(f, "That number is far too big to be the secret number."),
+            InputError::NotANumber(ref text) => write!(f, "'{}' is not a number.", text),
+            InputError::NotAnAnswer => write!(f, "Please answer + (your number is bigger), - (smaller) or = (that's it)."),
+            InputError::Contradiction => write!(f, "That can't be right - it doesn't fit your earlier answers."),
+        }
+    }
+}
+
+// turn one line typed by the player into a Command
+// low and high are the inclusive bounds of the current game so that we can reject guesses outside of it
+// the type of low and high decides what kind of number is parsed
+pub fn parse_command<T: Number>(line: &str, low: T, high: T) -> Result<Command<T>, InputError> {
+    // trim() removes the \n character created by read_line (and any other surrounding whitespace)
+    let text = line.trim();
+
+    if text.is_empty() {
+        return Err(InputError::Empty);
+    }
+
+    if is_quit(text) {
+        return Ok(Command::Quit);
+    }
+
+    let guess: T = match text.parse() {
+        Ok(num) => num,
+        // parse() only tells us that it failed, so we look at the text to work out why
+        Err(_) => return Err(classify(text, T::SIGNED)),
+    };
+
+    // f64 happily parses "inf" and "NaN", neither of which can be the secret number
+    if !guess.is_finite() {
+        return Err(InputError::NotANumber(text.to_string()));
+    }
+
+    if guess < low || guess > high {
+        return Err(InputError::OutOfRange {
+            low: low.to_string(),
+            high: high.to_string(),
+        });
+    }
+
+    Ok(Command::Guess(guess))
+}
+
+// does the player want to give up? shared by every game mode that reads from the prompt
+pub fn is_quit(line: &str) -> bool {
+    let text = line.trim();
+    text.eq_ignore_ascii_case("quit") || text.eq_ignore_ascii_case("q")
+}
+
+// the player's answer when the computer is the one guessing
+// it says how the guess compares with the player's number, so + (the number is bigger) means the guess was too small
+pub fn parse_answer(line: &str) -> Result<Outcome, InputError> {
+    match line.trim() {
+        "+" => Ok(Outcome::TooSmall),
+        "-" => Ok(Outcome::TooLarge),
+        "=" => Ok(Outcome::Correct),
+        _ => Err(InputError::NotAnAnswer),
+    }
+}
+
+// work out why some text could not be parsed as a number
+// `signed` says whether the number type allows negative numbers at all
+fn classify(text: &str, signed: bool) -> InputError {
+    let all_digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+
+    if !signed && text.starts_with('-') && all_digits(&text[1..]) {
+        InputError::Negative
+    } else if all_digits(text.trim_start_matches(['+', '-'])) {
+        // only digits, but parse() still failed - the number does not fit in the number type being parsed
+        InputError::Overflow
+    } else {
+        InputError::NotANumber(text.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Command, InputError> {
+        parse_command(line, 1u32, 100u32)
+    }
+
+    #[test]
+    fn a_number_in_range_is_a_guess() {
+        assert_eq!(parse("42\n"), Ok(Command::Guess(42)));
+        assert_eq!(parse("  1 "), Ok(Command::Guess(1)));
+        assert_eq!(parse("100"), Ok(Command::Guess(100)));
+    }
+
+    #[test]
+    fn quit_in_any_case_gives_up() {
+        assert_eq!(parse("quit"), Ok(Command::Quit));
+        assert_eq!(parse("QUIT\n"), Ok(Command::Quit));
+        assert_eq!(parse("q"), Ok(Command::Quit));
+    }
+
+    #[test]
+    fn blank_lines_are_empty() {
+        assert_eq!(parse(""), Err(InputError::Empty));
+        assert_eq!(parse("   \n"), Err(InputError::Empty));
+    }
+
+    #[test]
+    fn a_minus_sign_is_negative_for_unsigned_games() {
+        assert_eq!(parse("-5"), Err(InputError::Negative));
+    }
+
+    #[test]
+    fn numbers_outside_the_game_are_out_of_range() {
+        let error = InputError::OutOfRange {
+            low: "1".to_string(),
+            high: "100".to_string(),
+        };
+
+        assert_eq!(parse("0"), Err(error.clone()));
+        assert_eq!(parse("101"), Err(error));
+    }
+
+    #[test]
+    fn too_many_digits_is_an_overflow() {
+        assert_eq!(parse("99999999999999999999"), Err(InputError::Overflow));
+        assert_eq!(parse_command("-99999999999999999999", -10i64, 10i64), Err(InputError::Overflow));
+    }
+
+    #[test]
+    fn anything_else_is_not_a_number() {
+        assert_eq!(parse("fifty"), Err(InputError::NotANumber("fifty".to_string())));
+        assert_eq!(parse("4 2"), Err(InputError::NotANumber("4 2".to_string())));
+        assert_eq!(parse("-"), Err(InputError::NotANumber("-".to_string())));
+    }
+
+    #[test]
+    fn answers_are_plus_minus_or_equals() {
+        assert_eq!(parse_answer("+\n"), Ok(Outcome::TooSmall));
+        assert_eq!(parse_answer(" - "), Ok(Outcome::TooLarge));
+        assert_eq!(parse_answer("="), Ok(Outcome::Correct));
+        assert_eq!(parse_answer("bigger"), Err(InputError::NotAnAnswer));
+        assert_eq!(parse_answer(""), Err(InputError::NotAnAnswer));
+    }
+
+    #[test]
+    fn signed_games_accept_negative_guesses() {
+        assert_eq!(parse_command("-5", -10i64, 10i64), Ok(Command::Guess(-5)));
+    }
+
+    #[test]
+    fn infinity_and_nan_are_not_numbers() {
+        assert_eq!(parse_command("inf", 0.0, 1.0), Err(InputError::NotANumber("inf".to_string())));
+        assert_eq!(parse_command("NaN", 0.0, 1.0), Err(InputError::NotANumber("NaN".to_string())));
+    }
+}
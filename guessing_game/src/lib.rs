@@ -0,0 +1,30 @@
+// bring in the Rand external create we added as a dependency
+extern crate rand;
+// and the ChaCha generator behind seeded games, whose numbers are the same in every version of rand
+extern crate rand_chacha;
+
+// the library half of the crate holds all of the game logic so that it can be
+// tested and reused without going through stdin/stdout
+// main.rs is only a thin terminal front-end over these modules
+pub mod batch;
+pub mod bench;
+pub mod bulls;
+pub mod cli;
+pub mod clock;
+pub mod config;
+pub mod game;
+pub mod hint;
+pub mod http;
+pub mod input;
+pub mod messages;
+pub mod number;
+pub mod profiles;
+pub mod scores;
+pub mod server;
+pub mod session;
+pub mod solver;
+pub mod stats;
+pub mod transcript;
+pub mod tui;
+pub mod ulam;
+pub mod variant;
@@ -1,65 +1,999 @@
-// bring in the Rand external create we added as a dependency
+// all of the game logic lives in the library half of this crate (src/lib.rs)
+// this file is only the terminal front-end: it reads guesses and prints the results
+extern crate guessing_game;
 extern crate rand;
 
+// env gives us access to the command line arguments
+use std::env;
+use std::fs::File;
 // import the io library from the standard library (std)
-use std::io;
-// the Ordering enum allows us to compare numbers using the Less, Equal, and Greater variants
-use std::cmp::Ordering;
-// the Rng 'trait' defines methods that random number generators implement
-// this trait MUST BE IN SCOPE for us to use those methods
-use rand::Rng;
+// IsTerminal tells us whether stdout is a real terminal or a pipe/file
+use std::io::{self, BufReader, BufWriter, IsTerminal};
+use std::path::Path;
+// process::exit lets us end the program with a specific exit code
+use std::process;
+use std::time::Duration;
+
+use guessing_game::batch;
+use guessing_game::bench;
+use guessing_game::bulls::CodeGame;
+use guessing_game::cli::{self, NumberMode};
+use guessing_game::clock::{Clock, Countdown, MonotonicClock, Splits, Stopwatch};
+use guessing_game::config::{Config, Difficulty};
+use guessing_game::game::{Game, Outcome};
+use guessing_game::hint::{self, Thresholds};
+use guessing_game::http;
+use guessing_game::input::{self, Command};
+use guessing_game::messages::{Catalog, Key, Locale};
+use guessing_game::number::Number;
+use guessing_game::profiles;
+use guessing_game::scores::{self, ScoreEntry};
+use guessing_game::server;
+use guessing_game::session::{self, Session};
+use guessing_game::solver::{self, StrategyKind};
+use guessing_game::stats::{self, Filter};
+use guessing_game::transcript::{self, Event, Recorder};
+use guessing_game::tui::{self, TuiEnd};
+use guessing_game::ulam::{self, LyingGame};
+use guessing_game::variant::{GameVariant, Prompt, VariantKind};
+use rand::RngCore;
+
+// the --record file, if there is one
+type Transcript = Option<Recorder<BufWriter<File>>>;
+
+// the optional extras a round of the number game can be played with
+struct RoundExtras<'a> {
+    hints: Option<&'a Thresholds>,
+    transcript: Transcript,
+    // every timing question goes through this clock
+    clock: &'a dyn Clock,
+    // the --time-limit deadline for the current round
+    countdown: Option<Countdown>,
+    // the messages in the player's language
+    messages: Catalog,
+    // the rules being played, which decide how the end of a round is announced
+    variant: VariantKind,
+}
+
+// the ways a single round can end
+#[derive(Clone, Copy)]
+enum RoundEnd {
+    // the number was found or the player ran out of guesses
+    Finished,
+    // the player typed quit
+    Quit,
+    // stdin was closed part way through the round
+    Eof,
+}
+
+// read one line from stdin
+// returns None when stdin has been closed (EOF or Ctrl-D), so the caller can stop instead of looping forever
+fn read_line() -> Option<String> {
+    // let           - declare a variable (variables are immutable by default in Rust)
+    // mut           - makes the variable mutable
+    // String::new() - new is an 'associated function' of the String type (same as a static method)
+    let mut line = String::new();
+
+    // read_line() takes the input from stdin and stores it as a string in a variable
+    // it returns an io::Result with the number of bytes read - 0 bytes means there is nothing left to read
+    match io::stdin().read_line(&mut line) {
+        Ok(0) => None,
+        Ok(_) => Some(line),
+        Err(e) => {
+            eprintln!("Failed to read guess: {}", e);
+            None
+        }
+    }
+}
+
+// "1 guess" but "2 guesses"
+fn plural(count: u32, one: &str, many: &str) -> String {
+    format!("{} {}", count, if count == 1 { one } else { many })
+}
 
 fn main() {
-    // this defaults to a i32 (32bit integer) type
-    // :: is used to access associated functions
-    let secret_number = rand::thread_rng().gen_range(1, 101);
+    // skip(1) drops the program name so only the options are left
+    let options = match cli::parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, cli::USAGE);
+            process::exit(2);
+        }
+    };
+
+    if options.help {
+        println!("{}", cli::USAGE);
+        return;
+    }
+
+    // unwrap_or_else only builds the default path if --score-file was not given
+    let score_file = options.score_file.clone().unwrap_or_else(scores::default_path);
+    let profile_file = options.profile_file.clone().unwrap_or_else(profiles::default_path);
+
+    // --lang wins over the LANG environment variable
+    let messages = Catalog::new(options.locale.unwrap_or_else(Locale::from_env));
+
+    if options.show_scores {
+        print_scores(&score_file, &messages);
+        return;
+    }
+
+    if let Some(ref name) = options.show_profile {
+        print_profile(&profile_file, name, &messages);
+        return;
+    }
+
+    if let Some(ref filter) = options.stats {
+        print_stats(&score_file, filter, &messages);
+        return;
+    }
+
+    if let Some(games) = options.bench {
+        run_bench(&options, games);
+        return;
+    }
+
+    if let Some(ref path) = options.replay {
+        run_replay(path);
+        return;
+    }
+
+    if let Some(ref path) = options.batch {
+        run_batch(&options, path);
+        return;
+    }
+
+    if let Some(ref address) = options.serve {
+        run_server(&options, address);
+        return;
+    }
+
+    if let Some(ref address) = options.http {
+        run_http(&options, address);
+        return;
+    }
+
+    if let Some(length) = options.digits {
+        run_digits(&options, length, &messages);
+        return;
+    }
+
+    if let Some(numbers) = options.numbers {
+        let seed = options.seed;
+        match numbers {
+            NumberMode::U64(config) => run_numbers(config, 0, seed, &messages),
+            NumberMode::I64(config) => run_numbers(config, 0, seed, &messages),
+            NumberMode::F64 { config, epsilon } => run_numbers(config, epsilon, seed, &messages),
+        }
+        return;
+    }
+
+    if let Some(max_lies) = options.lies {
+        run_lying(&options, max_lies, &messages);
+        return;
+    }
+
+    if options.auto {
+        run_auto(&options, &messages);
+        return;
+    }
+
+    let transcript: Transcript = match options.record {
+        Some(ref path) => match File::create(path).and_then(|file| Recorder::new(BufWriter::new(file))) {
+            Ok(recorder) => Some(recorder),
+            Err(e) => {
+                eprintln!("Could not create transcript {}: {}", path.display(), e);
+                process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    // the full-screen interface only makes sense when someone is looking at a terminal
+    let use_tui = options.tui && io::stdout().is_terminal();
+    if options.tui && !use_tui {
+        eprintln!("stdout is not a terminal - using the plain prompt instead of --tui");
+    }
+
+    // the player's name is only asked for once, the first time a round needs saving
+    let mut player = options.player.clone();
+
+    // every round's secret number is drawn from this one session, so a seed repeats the whole sequence
+    let mut session = Session::new(options.config, session::rng_from_seed(options.seed));
+
+    let clock = MonotonicClock::new();
+    let mut extras = RoundExtras {
+        hints: options.hints.as_ref(),
+        transcript,
+        clock: &clock,
+        countdown: None,
+        messages,
+        variant: options.variant,
+    };
+    let mut splits = Splits::default();
 
     // println! is a 'Macro'
     // A macro is a powerful tool for defining rules in the language syntax
-    println!("\n==== Welcome to the Number Guessing Game! ====");
+    println!("\n{}", messages.text(Key::Welcome, &[]));
+    println!("{}", messages.text(Key::Difficulty, &[&options.difficulty]));
+    if options.variant != VariantKind::Classic {
+        println!("{}", messages.text(Key::Variant, &[&options.variant]));
+    }
+    if let Some(seed) = options.seed {
+        println!("{}", messages.text(Key::Seed, &[&seed]));
+    }
+    if let Some(secs) = options.time_limit {
+        println!("{}", messages.count(Key::TimeLimit, secs as u32));
+    }
+    if let Some(rounds) = options.speedrun {
+        println!("{}", messages.count(Key::SpeedRun, rounds));
+    }
+    println!("{}", messages.text(Key::TypeQuit, &[]));
+
+    loop {
+        // every variant, the classic game included, is played through the GameVariant trait
+        let mut game = session.next_variant(options.variant);
+        let stopwatch = Stopwatch::start(&clock);
+        extras.countdown = options.time_limit.map(|secs| Countdown::start(&clock, Duration::from_secs(secs)));
+
+        if let Some(single) = game.game() {
+            record(&mut extras.transcript, Event::Round { config: *single.config(), secret: single.secret() });
+        }
+
+        let end = if use_tui {
+            play_round_tui(game.as_mut(), &mut extras)
+        } else {
+            play_round(game.as_mut(), &mut extras)
+        };
+        let time = stopwatch.elapsed(&clock);
+
+        match end {
+            // the scores and achievements compare guesses at one secret number, so other variants are not saved
+            RoundEnd::Finished if options.variant.one_secret() => {
+                splits.push(time);
+                // get_or_insert_with only runs the closure when there is no name yet
+                let name = player.get_or_insert_with(|| ask_name(&messages)).clone();
+                let entry = finished_round(game.as_ref(), options.difficulty, name, time);
+                save_score(&score_file, &entry);
+                update_profile(&profile_file, &entry, &messages);
+            }
+            RoundEnd::Finished => splits.push(time),
+            RoundEnd::Quit => break,
+            RoundEnd::Eof => {
+                println!();
+                announce_end(game.as_ref(), options.variant, &messages, RoundEnd::Eof);
+                // a non-zero exit code tells the shell that the game did not finish normally
+                process::exit(1);
+            }
+        }
+
+        // a speed run goes straight on to the next round until enough have been played
+        if let Some(rounds) = options.speedrun {
+            if session.rounds() >= rounds {
+                break;
+            }
+            continue;
+        }
+
+        println!("\n{}", messages.text(Key::PlayAgain, &[]));
+        // running out of input here is fine - the round was already over
+        let again = read_line().is_some_and(|line| messages.is_yes(&line));
+        if !again {
+            break;
+        }
+    }
+
+    if options.speedrun.is_some() {
+        print_splits(&splits, &messages);
+    }
+}
+
+// the speed-run summary: the time for every round and the total
+// the numbers are padded before they go into the catalog's templates, so the columns still line up
+fn print_splits(splits: &Splits, messages: &Catalog) {
+    let secs = |time: Duration| format!("{:>8.2}", time.as_secs_f64());
+
+    println!("\n{}", messages.text(Key::SplitsTitle, &[]));
+    for (index, &time) in splits.rounds.iter().enumerate() {
+        println!("{}", messages.text(Key::SplitRound, &[&format!("{:>3}", index + 1), &secs(time)]));
+    }
+    if let (Some(fastest), Some(slowest)) = (splits.fastest(), splits.slowest()) {
+        println!("{}", messages.text(Key::SplitFastest, &[&secs(fastest)]));
+        println!("{}", messages.text(Key::SplitSlowest, &[&secs(slowest)]));
+    }
+    println!("{}", messages.text(Key::SplitTotal, &[&secs(splits.total())]));
+}
+
+// play one round in the terminal until it is won, lost or abandoned
+// with hint bands the player is also told how close each wrong guess was
+fn play_round(game: &mut dyn GameVariant, extras: &mut RoundExtras) -> RoundEnd {
+    let hints = extras.hints;
+    let clock = extras.clock;
+    let countdown = extras.countdown;
+    let messages = extras.messages;
+    let variant = extras.variant;
+    let transcript = &mut extras.transcript;
+
+    println!("\n{}", messages.rules(&game.rules()));
+    if let Some(max) = game.attempts_left() {
+        let key = if variant.player_guesses() { Key::GuessesToFind } else { Key::ComputerGuesses };
+        println!("{}", messages.count(key, max));
+    }
 
     // the 'loop' keyword creates an infinite loop
     loop {
-        println!("\nEnter your guess: ");
-
-        // let           - declare a variable (variables are immutable by default in Rust)
-        // mut           - makes the variable mutable
-        // String::new() - new is an 'associated function' of the String type (same as a static method)
-        let mut guess = String::new();
-
-        io::stdin()
-            // read_line() takes the input from stdin and stores it as a string in a variable
-            // in this case we pass in a reference to the guess variable
-            // read_line adds a new line character to the string (\n)
-            .read_line(&mut guess)
-            // This line catches errors
-            // read_line returns a value: io::Result (this is an enum type with variants 'Ok' or 'Err')
-            .expect("Failed to read guess...");
-
-        // however, we want a number from the guess, not a String!
-        // we can 'shadow' the variable name guess because we used the mut keyword to make it mutable
-        // : u32 reassigns the type of the variable and trim() removes the \n character created by read_line
-        // we can use match to stop the program from crashing when we don't get a number
-        let guess: u32 = match guess.trim().parse() {
-            // the Ok enum will contain the number if it is returned
-            Ok(num) => num,
-            // the _ is a catchall placeholder for anything that isn't a valid number
-            Err(_) => continue,
+        if let Some(countdown) = countdown {
+            let secs = countdown.remaining(clock).as_secs_f64().ceil() as u32;
+            println!("\n{}", messages.count(Key::SecondsLeft, secs));
+        }
+
+        // the variant decides what the player is asked - usually for a guess
+        let prompt = game.prompt();
+        let prompt_text = messages.prompt(&prompt);
+        println!("\n{}", prompt_text);
+        record(transcript, Event::Prompt(prompt_text));
+
+        let line = match read_line() {
+            Some(line) => line,
+            None => {
+                record(transcript, Event::End(String::from("eof")));
+                return RoundEnd::Eof;
+            }
+        };
+        record(transcript, Event::Input(line.trim_end().to_string()));
+
+        // a guess that arrives after the deadline does not count
+        if countdown.is_some_and(|countdown| countdown.expired(clock)) {
+            println!("{}", messages.text(Key::TimesUp, &[&game.reveal()]));
+            record(transcript, Event::End(String::from("timeout")));
+            return RoundEnd::Finished;
+        }
+
+        if input::is_quit(&line) {
+            announce_end(game, variant, &messages, RoundEnd::Quit);
+            record(transcript, Event::End(String::from("quit")));
+            return RoundEnd::Quit;
+        }
+
+        // the variant parses the line itself and explains anything it can't understand
+        let feedback = match game.play(&line) {
+            Ok(feedback) => feedback,
+            Err(e) => {
+                println!("{}", messages.input_error(&e));
+                continue;
+            }
         };
 
         // String templates can be created using the println! macro and a placeholder: {}
-        println!("You guessed {}", guess);
-
-        // we can use the 'match' keyword to compare variables with the cmp function
-        match guess.cmp(&secret_number) {
-            // each 'arm' of the match expression does something different based on the result of guess.cmp(&secret_number)
-            Ordering::Less => println!("Too small..."),
-            Ordering::Equal => {
-                println!("Congratulations! You guessed the number!");
-                // break refers to the infinite loop we created
-                break;
+        // the catalog's templates use numbered placeholders instead, so translations can reorder them
+        if prompt == Prompt::Guess {
+            println!("{}", messages.text(Key::YouGuessed, &[&line.trim()]));
+        }
+        println!("{}", messages.feedback(&feedback));
+
+        // the transcript and the hints are about a single secret, so only variants with one can have them
+        if let Some(single) = game.game() {
+            if let Some(attempt) = single.history().last() {
+                record(transcript, Event::Guess { guess: attempt.guess, outcome: attempt.outcome });
+            }
+            if !single.is_won() {
+                if let Some(hint) = hints.and_then(|thresholds| hint::for_last_guess(single, thresholds)) {
+                    println!("{}", messages.hint(&hint));
+                }
+            }
+        }
+
+        if game.is_won() {
+            announce_end(game, variant, &messages, RoundEnd::Finished);
+            record(transcript, Event::End(String::from("won")));
+            return RoundEnd::Finished;
+        }
+
+        if game.is_lost() {
+            announce_end(game, variant, &messages, RoundEnd::Finished);
+            record(transcript, Event::End(String::from("lost")));
+            return RoundEnd::Finished;
+        }
+
+        if let Some(left) = game.attempts_left() {
+            println!("{}", messages.count(Key::GuessesLeft, left));
+        }
+    }
+}
+
+// say how a round ended - who found the number depends on which side was guessing
+fn announce_end(game: &dyn GameVariant, variant: VariantKind, messages: &Catalog, end: RoundEnd) {
+    let player_guesses = variant.player_guesses();
+
+    let text = match end {
+        RoundEnd::Quit if player_guesses => messages.text(Key::GiveUp, &[&game.reveal()]),
+        RoundEnd::Quit => messages.text(Key::ComputerGiveUp, &[]),
+        RoundEnd::Eof => messages.text(Key::NoMoreInput, &[&game.reveal()]),
+        RoundEnd::Finished if game.is_won() && player_guesses => messages.count(Key::Congratulations, game.attempts()),
+        RoundEnd::Finished if game.is_won() => messages.count(Key::ComputerFound, game.attempts()),
+        RoundEnd::Finished if player_guesses => messages.text(Key::OutOfGuesses, &[&game.reveal()]),
+        RoundEnd::Finished => messages.text(Key::ComputerOutOfGuesses, &[&game.reveal()]),
+    };
+    println!("{}", text);
+}
+
+// the --digits mode: rounds of Bulls and Cows, using the same prompt loop as the number game
+fn run_digits(options: &cli::Options, length: usize, messages: &Catalog) {
+    let mut rng = session::rng_from_seed(options.seed);
+
+    println!("\n{}", messages.text(Key::CodeWelcome, &[]));
+    println!("{}", messages.text(Key::CodeRules, &[&length]));
+    println!("{}", messages.text(Key::CodeExplain, &[]));
+    println!("{}", messages.text(Key::TypeQuit, &[]));
+
+    loop {
+        let mut game = CodeGame::random(length, options.config.max_attempts, &mut rng);
+
+        match play_code_round(&mut game, messages) {
+            RoundEnd::Finished => {}
+            RoundEnd::Quit => break,
+            RoundEnd::Eof => {
+                println!("\n{}", messages.text(Key::CodeNoMoreInput, &[&game.secret()]));
+                process::exit(1);
+            }
+        }
+
+        println!("\n{}", messages.text(Key::PlayAgain, &[]));
+        let again = read_line().is_some_and(|line| messages.is_yes(&line));
+        if !again {
+            break;
+        }
+    }
+}
+
+fn play_code_round(game: &mut CodeGame, messages: &Catalog) -> RoundEnd {
+    if let Some(max) = game.attempts_left() {
+        println!("\n{}", messages.count(Key::GuessesToFind, max));
+    }
+
+    loop {
+        println!("\n{}", messages.text(Key::EnterGuess, &[]));
+
+        let line = match read_line() {
+            Some(line) => line,
+            None => return RoundEnd::Eof,
+        };
+
+        if input::is_quit(&line) {
+            println!("{}", messages.text(Key::CodeGiveUp, &[&game.secret()]));
+            return RoundEnd::Quit;
+        }
+
+        let score = match game.guess(&line) {
+            Ok(score) => score,
+            Err(e) => {
+                println!("{}", messages.code_error(&e));
+                continue;
+            }
+        };
+
+        println!("{} - {}", messages.text(Key::YouGuessed, &[&line.trim()]), messages.score(&score));
+
+        if game.is_won() {
+            println!("{}", messages.count(Key::CodeCracked, game.attempts()));
+            return RoundEnd::Finished;
+        }
+
+        if game.is_lost() {
+            println!("{}", messages.text(Key::CodeOutOfGuesses, &[&game.secret()]));
+            return RoundEnd::Finished;
+        }
+
+        if let Some(left) = game.attempts_left() {
+            println!("{}", messages.count(Key::GuessesLeft, left));
+        }
+    }
+}
+
+// the --number modes: the same game with 64-bit, negative or decimal numbers
+// T is decided by the config that is passed in, so one function plays all of them
+fn run_numbers<T: Number>(config: Config<T>, tolerance: T, seed: Option<u64>, messages: &Catalog) {
+    let mut rng = session::rng_from_seed(seed);
+    let mut game = Game::random(config, &mut rng).with_tolerance(tolerance);
+
+    println!("\n{}", messages.text(Key::Welcome, &[]));
+    println!("{}", messages.text(Key::ThinkingOf, &[&game.low(), &game.high()]));
+    if tolerance > T::zero() {
+        println!("{}", messages.text(Key::Tolerance, &[&tolerance]));
+    }
+    if let Some(max) = game.attempts_left() {
+        println!("{}", messages.count(Key::GuessesToFind, max));
+    }
+    println!("{}", messages.text(Key::TypeQuit, &[]));
+
+    while !game.is_over() {
+        println!("\n{}", messages.text(Key::EnterGuess, &[]));
+
+        let line = match read_line() {
+            Some(line) => line,
+            None => {
+                println!("\n{}", messages.text(Key::NoMoreInput, &[&game.secret()]));
+                process::exit(1);
             }
-            Ordering::Greater => println!("Too large..."),
+        };
+
+        let guess = match input::parse_command(&line, game.low(), game.high()) {
+            Ok(Command::Guess(num)) => num,
+            Ok(Command::Quit) => {
+                println!("{}", messages.text(Key::GiveUp, &[&game.secret()]));
+                return;
+            }
+            Err(e) => {
+                println!("{}", messages.input_error(&e));
+                continue;
+            }
+        };
+
+        match game.guess(guess) {
+            Outcome::Correct => println!("{}", messages.count(Key::Congratulations, game.attempts())),
+            outcome => println!("{}", messages.outcome(outcome)),
+        }
+    }
+
+    if game.is_lost() {
+        println!("{}", messages.text(Key::OutOfGuesses, &[&game.secret()]));
+    }
+}
+
+// the --lies mode: Ulam's game, played by the player or (with --auto) by the computer
+fn run_lying(options: &cli::Options, max_lies: u32, messages: &Catalog) {
+    let mut rng = session::rng_from_seed(options.seed);
+    let mut game = LyingGame::random(options.config, max_lies, options.liar, &mut rng);
+
+    println!("\n{}", messages.text(Key::LyingWelcome, &[]));
+    println!("{}", messages.text(Key::ThinkingOf, &[&game.low(), &game.high()]));
+    println!("{}", messages.text(Key::LyingRules, &[&max_lies, &messages.liar(game.policy())]));
+    println!("{}", messages.text(Key::LyingNeverLies, &[]));
+
+    let end = if options.auto {
+        ulam::solve(&mut game, &mut rng);
+        RoundEnd::Finished
+    } else {
+        if let Some(max) = game.attempts_left() {
+            println!("{}", messages.count(Key::GuessesToFind, max));
+        }
+        play_lying_round(&mut game, &mut rng, messages)
+    };
+
+    if game.is_won() {
+        println!("\n{}", messages.count_with(Key::LyingFound, game.attempts(), &[&game.secret()]));
+    } else {
+        println!("\n{}", messages.text(Key::TheNumberWas, &[&game.secret()]));
+    }
+
+    // the reveal: every answer, with the lies marked
+    println!("\n{}", messages.count(Key::LiesTold, game.lies_told()));
+    for (index, answer) in game.answers().iter().enumerate() {
+        let told = messages.outcome_word(answer.told);
+        let mark = if answer.is_lie() { format!("  {}", messages.text(Key::LieMark, &[])) } else { String::new() };
+        println!("  {:>3}. {:>10}  {}{}", index + 1, answer.guess, told, mark);
+    }
+
+    if let RoundEnd::Eof = end {
+        process::exit(1);
+    }
+}
+
+fn play_lying_round(game: &mut LyingGame, rng: &mut dyn RngCore, messages: &Catalog) -> RoundEnd {
+    while !game.is_over() {
+        println!("\n{}", messages.text(Key::EnterGuess, &[]));
+
+        let line = match read_line() {
+            Some(line) => line,
+            None => {
+                println!("\n{}", messages.text(Key::InputEnded, &[]));
+                return RoundEnd::Eof;
+            }
+        };
+
+        let guess = match input::parse_command(&line, game.low(), game.high()) {
+            Ok(Command::Guess(num)) => num,
+            Ok(Command::Quit) => return RoundEnd::Quit,
+            Err(e) => {
+                println!("{}", messages.input_error(&e));
+                continue;
+            }
+        };
+
+        match game.guess(guess, rng) {
+            Outcome::TooSmall => println!("{}", messages.text(Key::LyingTooSmall, &[])),
+            Outcome::TooLarge => println!("{}", messages.text(Key::LyingTooLarge, &[])),
+            Outcome::Correct => {}
+        }
+    }
+
+    RoundEnd::Finished
+}
+
+// play one round with the full-screen interface, then report the result on the normal screen
+fn play_round_tui(game: &mut dyn GameVariant, extras: &mut RoundExtras) -> RoundEnd {
+    let hints = extras.hints;
+    let messages = extras.messages;
+    let variant = extras.variant;
+    let transcript = &mut extras.transcript;
+
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+
+    let end = match tui::play_round(game, hints, &messages, stdin.lock(), stdout.lock()) {
+        Ok(end) => end,
+        Err(e) => {
+            eprintln!("The terminal interface failed: {}", e);
+            process::exit(1);
+        }
+    };
+
+    // the TUI draws straight to the terminal, so the transcript is filled in from the history afterwards
+    if let Some(single) = game.game() {
+        for attempt in single.history() {
+            record(transcript, Event::Guess { guess: attempt.guess, outcome: attempt.outcome });
+        }
+    }
+
+    let (end, how) = match end {
+        TuiEnd::Finished if game.is_won() => (RoundEnd::Finished, "won"),
+        TuiEnd::Finished => (RoundEnd::Finished, "lost"),
+        TuiEnd::Quit => (RoundEnd::Quit, "quit"),
+        // main reports running out of input itself
+        TuiEnd::Eof => {
+            record(transcript, Event::End(String::from("eof")));
+            return RoundEnd::Eof;
+        }
+    };
+
+    record(transcript, Event::End(how.to_string()));
+    announce_end(game, variant, &messages, end);
+    end
+}
+
+// add an event to the transcript, if one is being recorded
+// a failed write stops the recording (with a warning) rather than the game
+fn record(transcript: &mut Transcript, event: Event) {
+    let failed = match *transcript {
+        Some(ref mut recorder) => recorder.record(&event).is_err(),
+        None => false,
+    };
+
+    if failed {
+        eprintln!("Could not write to the transcript - recording stopped.");
+        *transcript = None;
+    }
+}
+
+// ask for the name to save with the scores - an empty answer (or no input at all) saves as anonymous
+fn ask_name(messages: &Catalog) -> String {
+    println!("\n{}", messages.text(Key::EnterName, &[]));
+    read_line().unwrap_or_default().trim().to_string()
+}
+
+// the score file entry for a finished round
+fn finished_round(game: &dyn GameVariant, difficulty: Difficulty, player: String, duration: Duration) -> ScoreEntry {
+    let config = game.config();
+
+    ScoreEntry {
+        player,
+        difficulty,
+        low: config.low,
+        high: config.high,
+        attempts: game.attempts(),
+        won: game.is_won(),
+        duration,
+        finished_at: scores::now(),
+    }
+}
+
+// append a finished round to the score file
+// failing to save is reported but never stops the game
+fn save_score(path: &Path, entry: &ScoreEntry) {
+    if let Err(e) = scores::append(path, entry) {
+        eprintln!("Could not save your score to {}: {}", path.display(), e);
+    }
+}
+
+// add the round to the player's profile and announce any achievements it unlocked
+// anonymous rounds have nobody to give the achievements to, so they are left out
+fn update_profile(path: &Path, entry: &ScoreEntry, messages: &Catalog) {
+    if entry.player.trim().is_empty() {
+        return;
+    }
+
+    match profiles::record(path, entry) {
+        Ok(unlocked) => {
+            for achievement in unlocked {
+                println!("\n{}", messages.text(Key::AchievementUnlocked, &[&messages.achievement(achievement)]));
+            }
+        }
+        Err(e) => eprintln!("Could not update your profile in {}: {}", path.display(), e),
+    }
+}
+
+// the profile subcommand: one player's record and which achievements they have
+fn print_profile(path: &Path, name: &str, messages: &Catalog) {
+    let loaded = match profiles::load(path) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("Could not read profiles from {}: {}", path.display(), e);
+            process::exit(1);
+        }
+    };
+
+    let profile = match loaded.get(name) {
+        Some(profile) => profile,
+        None => {
+            println!("\n{}", messages.text(Key::NoProfile, &[&name]));
+            return;
+        }
+    };
+
+    println!("\n==== {} ====", profile.name);
+    println!("{}", messages.text(Key::RoundsPlayed, &[&profile.played]));
+    println!("{}", messages.text(Key::RoundsWon, &[&profile.won]));
+    println!("{}", messages.text(Key::CurrentStreak, &[&messages.count(Key::Wins, profile.streak)]));
+    println!("{}", messages.text(Key::BestStreak, &[&messages.count(Key::Wins, profile.best_streak)]));
+
+    let unlocked = profile.achievements.len();
+    println!("\n{}", messages.text(Key::Achievements, &[&unlocked, &profiles::Achievement::ALL.len()]));
+    for &achievement in profiles::Achievement::ALL.iter() {
+        let mark = if profile.achievements.contains(&achievement) { "x" } else { " " };
+        println!("  [{}] {}", mark, messages.achievement(achievement));
+    }
+}
+
+// the --scores view: the best won rounds for each difficulty
+fn print_scores(path: &Path, messages: &Catalog) {
+    let loaded = match scores::load(path) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("Could not read scores from {}: {}", path.display(), e);
+            process::exit(1);
+        }
+    };
+
+    println!("\n{}", messages.text(Key::HighScores, &[]));
+
+    let difficulties = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard, Difficulty::Custom];
+    for &difficulty in difficulties.iter() {
+        let best = scores::best(&loaded.entries, difficulty, scores::TOP_N);
+        if best.is_empty() {
+            continue;
+        }
+
+        println!("\n{}", difficulty);
+        println!("{}", messages.text(Key::ScoresHeader, &[]));
+        for entry in best {
+            // {:<16} pads the text to 16 characters, {:>8} right-aligns it in 8
+            println!(
+                "  {:<16} {:>10} {:>8} {:>8.1}s  {}",
+                entry.player,
+                format!("{}-{}", entry.low, entry.high),
+                entry.attempts,
+                entry.duration.as_secs_f64(),
+                scores::format_date(entry.finished_at)
+            );
+        }
+    }
+
+    if loaded.entries.iter().all(|entry| !entry.won) {
+        println!("\n{}", messages.text(Key::NoWins, &[]));
+    }
+
+    if loaded.skipped > 0 {
+        println!("\n{}", messages.count_with(Key::Skipped, loaded.skipped as u32, &[&path.display()]));
+    }
+}
+
+// the --auto mode: the computer plays one round while we watch
+fn run_auto(options: &cli::Options, messages: &Catalog) {
+    let kind = options.strategy.unwrap_or(StrategyKind::BinarySearch);
+    let mut session = Session::new(options.config, session::rng_from_seed(options.seed));
+    let mut game = session.next_game();
+
+    println!("\n{}", messages.text(Key::AutoTitle, &[&kind]));
+    println!("{}", messages.text(Key::AutoRange, &[&game.low(), &game.high()]));
+
+    let result = solver::solve(&mut game, kind.build().as_mut(), session.rng());
+
+    for attempt in game.history() {
+        println!("{}", messages.text(Key::ComputerGuessed, &[&attempt.guess, &messages.outcome_word(attempt.outcome)]));
+    }
+
+    match result {
+        Ok(true) => println!("{}", messages.count_with(Key::AutoFound, game.attempts(), &[&game.secret()])),
+        Ok(false) => println!("{}", messages.text(Key::OutOfGuesses, &[&game.secret()])),
+        Err(e) => {
+            eprintln!("The game gave inconsistent feedback: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+// the stats subcommand: totals, streaks and a histogram of guesses for the rounds in the score file
+fn print_stats(path: &Path, filter: &Filter, messages: &Catalog) {
+    let loaded = match scores::load(path) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("Could not read scores from {}: {}", path.display(), e);
+            process::exit(1);
+        }
+    };
+
+    let stats = stats::compute(&loaded.entries, filter);
+
+    // describe the filter in the heading, e.g. "hard, alice, from 2024-01-01"
+    let mut scope = Vec::new();
+    if let Some(difficulty) = filter.difficulty {
+        scope.push(difficulty.to_string());
+    }
+    if let Some(ref player) = filter.player {
+        scope.push(player.clone());
+    }
+    if let Some(from) = filter.from {
+        scope.push(messages.text(Key::StatsFrom, &[&scores::format_date(from)]));
+    }
+    if let Some(until) = filter.until {
+        scope.push(messages.text(Key::StatsTo, &[&scores::format_date(until - 86_400)]));
+    }
+    let scope = if scope.is_empty() { messages.text(Key::AllRounds, &[]) } else { scope.join(", ") };
+
+    println!("\n{}", messages.text(Key::StatsTitle, &[&scope]));
+
+    let win_rate = match stats.win_rate() {
+        Some(rate) => rate,
+        None => {
+            println!("\n{}", messages.text(Key::NoRounds, &[]));
+            return;
+        }
+    };
+
+    println!("{}", messages.text(Key::GamesPlayed, &[&stats.played]));
+    println!("{}", messages.text(Key::GamesWon, &[&stats.won, &format!("{:.1}", win_rate * 100.0)]));
+    if let (Some(mean), Some(median)) = (stats.mean_attempts, stats.median_attempts) {
+        println!("{}", messages.text(Key::AverageGuesses, &[&format!("{:.2}", mean)]));
+        println!("{}", messages.text(Key::MedianGuesses, &[&median]));
+    }
+    let current = messages.count(Key::Wins, stats.current_streak as u32);
+    let longest = messages.count(Key::Wins, stats.longest_streak as u32);
+    println!("{}", messages.text(Key::CurrentStreak, &[&current]));
+    println!("{}", messages.text(Key::LongestStreak, &[&longest]));
+
+    // the same bars as --bench: the biggest group is 40 characters wide, lost rounds get a row of their own
+    let mut rows: Vec<(String, usize)> = stats
+        .histogram
+        .iter()
+        .map(|(&attempts, &count)| (attempts.to_string(), count))
+        .collect();
+    if stats.lost() > 0 {
+        rows.push((messages.text(Key::Lost, &[]), stats.lost()));
+    }
+
+    println!("\n{}", messages.text(Key::GuessesPerGame, &[]));
+    let most = rows.iter().map(|row| row.1).max().unwrap_or(1);
+    for (label, count) in rows {
+        let bar = "#".repeat(((count * 40) / most).max(1));
+        println!("  {:>9} | {:<40} {}", label, bar, count);
+    }
+
+    if loaded.skipped > 0 {
+        println!("\n{}", messages.count_with(Key::Skipped, loaded.skipped as u32, &[&path.display()]));
+    }
+}
+
+// the --bench mode: lots of computer games per strategy, then a summary of each
+fn run_bench(options: &cli::Options, games: u32) {
+    // a single --strategy limits the benchmark to that one, otherwise every strategy is compared
+    let kinds = match options.strategy {
+        Some(kind) => vec![kind],
+        None => StrategyKind::ALL.to_vec(),
+    };
+
+    println!("\n==== {} games per strategy, numbers {} to {} ====", games, options.config.low, options.config.high);
+
+    for kind in kinds {
+        // every strategy gets the same seed, so they all face the same secret numbers
+        let report = match bench::run(kind, options.config, games, session::rng_from_seed(options.seed)) {
+            Ok(report) => report,
+            Err(e) => {
+                eprintln!("{}: the game gave inconsistent feedback: {}", kind, e);
+                process::exit(1);
+            }
+        };
+
+        println!("\n{}: mean {:.2} guesses, worst case {}", kind, report.mean, report.worst);
+
+        // scale the bars so that the biggest group is 40 characters wide
+        let buckets = report.buckets(20);
+        let most = buckets.iter().map(|bucket| bucket.2).max().unwrap_or(1);
+        for (first, last, count) in buckets {
+            let label = if first == last { first.to_string() } else { format!("{}-{}", first, last) };
+            let bar = "#".repeat(((count * 40) / most).max(1) as usize);
+            println!("  {:>9} | {:<40} {}", label, bar, count);
+        }
+    }
+}
+
+// the --serve mode: host a game that players join over TCP (e.g. with `nc 127.0.0.1 7878`)
+fn run_server(options: &cli::Options, address: &str) {
+    let listener = match server::bind(address) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Could not listen on {}: {}", address, e);
+            process::exit(1);
+        }
+    };
+
+    println!("Hosting a {} game on {} - press Ctrl-C to stop", options.mode, address);
+
+    if let Err(e) = server::serve(listener, options.config, options.mode, options.seed) {
+        eprintln!("Server stopped: {}", e);
+        process::exit(1);
+    }
+}
+
+// the --http mode: the game as a JSON API for other programs on this machine
+fn run_http(options: &cli::Options, address: &str) {
+    let listener = match http::bind(address) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Could not listen on {}: {}", address, e);
+            process::exit(1);
+        }
+    };
+
+    println!("Serving the HTTP API on http://{} - press Ctrl-C to stop", address);
+
+    if let Err(e) = http::serve(listener, Duration::from_secs(options.session_timeout)) {
+        eprintln!("Server stopped: {}", e);
+        process::exit(1);
+    }
+}
+
+// the replay subcommand: run a recorded transcript against the game and check every outcome
+fn run_replay(path: &Path) {
+    let entries = File::open(path)
+        .map_err(transcript::TranscriptError::from)
+        .and_then(|file| transcript::read(BufReader::new(file)));
+
+    let result = entries.and_then(|entries| transcript::replay(&entries));
+
+    match result {
+        Ok(summary) => println!(
+            "Replayed {} and {}: every outcome matches.",
+            plural(summary.rounds, "round", "rounds"),
+            plural(summary.guesses, "guess", "guesses")
+        ),
+        Err(e) => {
+            eprintln!("Replay of {} failed: {}", path.display(), e);
+            process::exit(1);
+        }
+    }
+}
+
+// the --batch mode: guesses from a file or stdin, JSON lines on stdout, and an exit code for the result
+fn run_batch(options: &cli::Options, path: &Path) {
+    let mut session = Session::new(options.config, session::rng_from_seed(options.seed));
+    let mut game = session.next_game();
+    let stdout = io::stdout();
+
+    let result = if path == Path::new("-") {
+        let stdin = io::stdin();
+        batch::run(&mut game, stdin.lock(), stdout.lock())
+    } else {
+        match File::open(path) {
+            Ok(file) => batch::run(&mut game, BufReader::new(file), stdout.lock()),
+            Err(e) => Err(e),
+        }
+    };
+
+    match result {
+        Ok(result) => process::exit(result.exit_code()),
+        Err(e) => {
+            eprintln!("Batch run on {} failed: {}", path.display(), e);
+            process::exit(batch::EXIT_INPUT_ERROR);
         }
     }
 }
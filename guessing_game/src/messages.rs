@@ -0,0 +1,774 @@
+// the message catalog: what the terminal game says to the player, in each bundled language
+//
+// that is every mode's rounds and the scores, stats and profile views
+// errors on stderr, the usage text, --bench, replay and the --serve/--http servers stay in English -
+// they are for whoever runs the program rather than for the player
+//
+// messages use numbered placeholders - {0}, {1} - so a translation can put them in a different order
+// a message that depends on a count has a singular and a plural form separated by '|'
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::bulls::{CodeError, Score};
+use crate::game::Outcome;
+use crate::hint::{Band, Hint, Trend};
+use crate::input::InputError;
+use crate::profiles::{Achievement, STREAK_GOAL};
+use crate::ulam::LiePolicy;
+use crate::variant::{Feedback, Prompt, Rules};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    English,
+    Spanish,
+}
+
+impl Locale {
+    // understand locale names the way LANG writes them: "es", "es_ES", "es_ES.UTF-8", "en-GB" ...
+    // only the language part before any '_', '-' or '.' matters
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let language = tag.split(['_', '-', '.']).next().unwrap_or("");
+
+        match language.to_ascii_lowercase().as_str() {
+            "en" | "c" | "posix" => Some(Locale::English),
+            "es" => Some(Locale::Spanish),
+            _ => None,
+        }
+    }
+
+    // the locale from the environment: LC_ALL wins over LANG, but a value we don't know is skipped
+    // and English is used when neither says anything we understand
+    pub fn from_env() -> Locale {
+        ["LC_ALL", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .filter_map(|value| Locale::from_tag(&value))
+            .next()
+            .unwrap_or(Locale::English)
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Locale, String> {
+        Locale::from_tag(s).ok_or_else(|| format!("unsupported language '{}' (expected en or es)", s))
+    }
+}
+
+// one entry in the catalog per message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Welcome,
+    Difficulty,
+    Variant,
+    Seed,
+    TimeLimit,
+    SpeedRun,
+    TypeQuit,
+    ThinkingOf,
+    GuessesToFind,
+    ComputerGuesses,
+    RulesShrinking,
+    RulesSeveral,
+    RulesReverse,
+    SecondsLeft,
+    EnterGuess,
+    JudgeGuess,
+    TimesUp,
+    GiveUp,
+    YouGuessed,
+    TooSmall,
+    TooLarge,
+    Correct,
+    // the answer to a guess, then the range that is left: "Too small... it is between 51 and 100."
+    Narrowed,
+    FoundOne,
+    FoundLast,
+    Missed,
+    Congratulations,
+    OutOfGuesses,
+    ComputerGiveUp,
+    ComputerFound,
+    ComputerOutOfGuesses,
+    GuessesLeft,
+    NoMoreInput,
+    PlayAgain,
+    // the answer to PlayAgain that means yes
+    Yes,
+    EnterName,
+    InputEmpty,
+    InputNegative,
+    InputOutOfRange,
+    InputOverflow,
+    InputNotANumber,
+    InputNotAnAnswer,
+    InputContradiction,
+    // hot/cold hints: the band, then how it compares with the guess before
+    Hint,
+    HintTrend,
+    Freezing,
+    Cold,
+    Warm,
+    Hot,
+    Warmer,
+    Colder,
+    NoCloser,
+    // the speed-run summary
+    SplitsTitle,
+    SplitRound,
+    SplitFastest,
+    SplitSlowest,
+    SplitTotal,
+    // Bulls and Cows (--digits)
+    CodeWelcome,
+    CodeRules,
+    CodeExplain,
+    Bulls,
+    Cows,
+    CodeGiveUp,
+    CodeCracked,
+    CodeOutOfGuesses,
+    CodeNoMoreInput,
+    CodeEmpty,
+    CodeWrongLength,
+    CodeNotADigit,
+    CodeRepeatedDigit,
+    // the --number modes
+    Tolerance,
+    // Ulam's game (--lies)
+    LyingWelcome,
+    LyingRules,
+    LyingNeverLies,
+    LiarRandom,
+    LiarAdversarial,
+    LyingTooSmall,
+    LyingTooLarge,
+    LyingFound,
+    TheNumberWas,
+    LiesTold,
+    LieMark,
+    InputEnded,
+    // an outcome in a list of guesses, in lower case: "42  too small"
+    WordTooSmall,
+    WordTooLarge,
+    WordCorrect,
+    // the computer playing on its own (--auto)
+    AutoTitle,
+    AutoRange,
+    ComputerGuessed,
+    AutoFound,
+    // achievements and the profile view
+    AchievementUnlocked,
+    FirstTry,
+    Optimal,
+    Streak,
+    HardCleared,
+    NoProfile,
+    RoundsPlayed,
+    RoundsWon,
+    CurrentStreak,
+    BestStreak,
+    Wins,
+    Achievements,
+    // the --scores view
+    HighScores,
+    ScoresHeader,
+    NoWins,
+    Skipped,
+    // the stats view
+    StatsTitle,
+    AllRounds,
+    StatsFrom,
+    StatsTo,
+    NoRounds,
+    GamesPlayed,
+    GamesWon,
+    AverageGuesses,
+    MedianGuesses,
+    LongestStreak,
+    GuessesPerGame,
+    Lost,
+    // the full-screen interface (--tui)
+    TuiTitle,
+    TuiStillPossible,
+    TuiGuessesLeft,
+    TuiGuessesSoFar,
+    TuiHistory,
+    TuiNoGuesses,
+    TuiTypeNumber,
+    TuiTypeAnswer,
+    TuiGuessPrompt,
+    TuiJudgePrompt,
+}
+
+fn english(key: Key) -> &'static str {
+    match key {
+        Key::Welcome => "==== Welcome to the Number Guessing Game! ====",
+        Key::Difficulty => "Difficulty: {0}",
+        Key::Variant => "Rules: {0}",
+        Key::Seed => "Seed: {0}",
+        Key::TimeLimit => {
+            "You have {0} second for each round - a guess entered after that loses it.|You have {0} seconds for each round - a guess entered after that loses it."
+        }
+        Key::SpeedRun => "Speed run: {0} round - the clock is running!|Speed run: {0} rounds back to back - the clock is running!",
+        Key::TypeQuit => "Type 'quit' or 'q' at any time to give up.",
+        Key::ThinkingOf => "I'm thinking of a number between {0} and {1}.",
+        Key::GuessesToFind => "You have {0} guess to find it.|You have {0} guesses to find it.",
+        Key::ComputerGuesses => "I have {0} guess to find it.|I have {0} guesses to find it.",
+        Key::RulesShrinking => {
+            "I'm thinking of a number between {0} and {1}.\nEvery answer shrinks the range, and you must guess inside it."
+        }
+        Key::RulesSeveral => {
+            "I'm thinking of {0} different numbers between {1} and {2}. Find them all!\nA miss tells you how many are below and above your guess."
+        }
+        Key::RulesReverse => {
+            "Think of a number between {0} and {1} and I will guess it.\nAnswer + if your number is bigger, - if it is smaller and = when I get it."
+        }
+        Key::SecondsLeft => "{0} second left.|{0} seconds left.",
+        Key::EnterGuess => "Enter your guess: ",
+        Key::JudgeGuess => "Is it {0}? (+ bigger, - smaller, = yes): ",
+        Key::TimesUp => "Time's up! The number was {0}.",
+        Key::GiveUp => "Giving up already? The number was {0}.",
+        Key::YouGuessed => "You guessed {0}",
+        Key::TooSmall => "Too small...",
+        Key::TooLarge => "Too large...",
+        Key::Correct => "Correct!",
+        Key::Narrowed => "{0} it is between {1} and {2}.",
+        Key::FoundOne => "Found one! {0} still hidden.",
+        Key::FoundLast => "Found one - and that was the last!",
+        Key::Missed => "Missed - {0} below, {1} above.",
+        Key::Congratulations => {
+            "Congratulations! You guessed the number in {0} guess!|Congratulations! You guessed the number in {0} guesses!"
+        }
+        Key::OutOfGuesses => "Out of guesses! The number was {0}.",
+        Key::ComputerGiveUp => "Fine - keep your number a secret.",
+        Key::ComputerFound => "Got it! I found your number in {0} guess.|Got it! I found your number in {0} guesses.",
+        Key::ComputerOutOfGuesses => "I'm out of guesses! Your number is somewhere in {0}.",
+        Key::GuessesLeft => "{0} guess left.|{0} guesses left.",
+        Key::NoMoreInput => "No more input - the number was {0}.",
+        Key::PlayAgain => "Play again? (y/n): ",
+        Key::Yes => "y",
+        Key::EnterName => "Enter your name: ",
+        Key::InputEmpty => "Please type a number (or 'quit' to give up).",
+        Key::InputNegative => "The secret number is never negative.",
+        Key::InputOutOfRange => "Your guess must be between {0} and {1}.",
+        Key::InputOverflow => "That number is far too big to be the secret number.",
+        Key::InputNotANumber => "'{0}' is not a number.",
+        Key::InputNotAnAnswer => "Please answer + (your number is bigger), - (smaller) or = (that's it).",
+        Key::InputContradiction => "That can't be right - it doesn't fit your earlier answers.",
+        Key::Hint => "{0}.",
+        Key::HintTrend => "{0} - {1}.",
+        Key::Freezing => "Freezing",
+        Key::Cold => "Cold",
+        Key::Warm => "Warm",
+        Key::Hot => "Hot",
+        Key::Warmer => "warmer than last time",
+        Key::Colder => "colder than last time",
+        Key::NoCloser => "no closer than last time",
+        Key::SplitsTitle => "==== Speed run ====",
+        Key::SplitRound => "  round {0}: {1}s",
+        Key::SplitFastest => "  fastest:   {0}s",
+        Key::SplitSlowest => "  slowest:   {0}s",
+        Key::SplitTotal => "  total:     {0}s",
+        Key::CodeWelcome => "==== Welcome to Bulls and Cows! ====",
+        Key::CodeRules => "I'm thinking of a {0}-digit code with no repeated digits.",
+        Key::CodeExplain => "A bull is a right digit in the right place, a cow is a right digit in the wrong place.",
+        Key::Bulls => "{0} bull|{0} bulls",
+        Key::Cows => "{0} cow|{0} cows",
+        Key::CodeGiveUp => "Giving up already? The code was {0}.",
+        Key::CodeCracked => {
+            "Congratulations! You cracked the code in {0} guess!|Congratulations! You cracked the code in {0} guesses!"
+        }
+        Key::CodeOutOfGuesses => "Out of guesses! The code was {0}.",
+        Key::CodeNoMoreInput => "No more input - the code was {0}.",
+        Key::CodeEmpty => "Please type a code (or 'quit' to give up).",
+        Key::CodeWrongLength => "The code has {0} digits, but you typed {1}.",
+        Key::CodeNotADigit => "'{0}' is not a digit.",
+        Key::CodeRepeatedDigit => "The digit {0} is used more than once - every digit is different.",
+        Key::Tolerance => "Any guess within {0} of it counts.",
+        Key::LyingWelcome => "==== Welcome to Ulam's Game! ====",
+        Key::LyingRules => "Careful: up to {0} of my answers may be lies ({1}).",
+        Key::LyingNeverLies => "I never lie when you name the number itself.",
+        Key::LiarRandom => "random ({0}% chance per answer)",
+        Key::LiarAdversarial => "adversarial",
+        Key::LyingTooSmall => "Too small... (or is it?)",
+        Key::LyingTooLarge => "Too large... (or is it?)",
+        Key::LyingFound => "{1} is right, found in {0} guess!|{1} is right, found in {0} guesses!",
+        Key::TheNumberWas => "The number was {0}.",
+        Key::LiesTold => "I told {0} lie:|I told {0} lies:",
+        Key::LieMark => "<- lie!",
+        Key::InputEnded => "No more input.",
+        Key::WordTooSmall => "too small",
+        Key::WordTooLarge => "too large",
+        Key::WordCorrect => "correct",
+        Key::AutoTitle => "==== The computer ({0}) is playing ====",
+        Key::AutoRange => "The number is between {0} and {1}.",
+        Key::ComputerGuessed => "Computer guessed {0} - {1}",
+        Key::AutoFound => "Found {1} in {0} guess.|Found {1} in {0} guesses.",
+        Key::AchievementUnlocked => "*** Achievement unlocked: {0} ***",
+        Key::FirstTry => "Beginner's Luck - win a round with your first guess",
+        Key::Optimal => "Binary Brain - win in fewer guesses than binary search could need",
+        Key::Streak => "On a Roll - win {0} rounds in a row",
+        Key::HardCleared => "Hard as Nails - win a round on hard",
+        Key::NoProfile => "No profile for {0} yet - finish a round with --name {0} to start one.",
+        Key::RoundsPlayed => "  rounds played:     {0}",
+        Key::RoundsWon => "  rounds won:        {0}",
+        Key::CurrentStreak => "  current streak:    {0}",
+        Key::BestStreak => "  best streak:       {0}",
+        Key::Wins => "{0} win|{0} wins",
+        Key::Achievements => "  achievements ({0} of {1})",
+        Key::HighScores => "==== High Scores ====",
+        Key::ScoresHeader => "  player                range  guesses      time  date",
+        Key::NoWins => "No games won yet - go and play one!",
+        Key::Skipped => "({0} unreadable line in {1} was skipped)|({0} unreadable lines in {1} were skipped)",
+        Key::StatsTitle => "==== Statistics ({0}) ====",
+        Key::AllRounds => "all rounds",
+        Key::StatsFrom => "from {0}",
+        Key::StatsTo => "to {0}",
+        Key::NoRounds => "No rounds found - go and play one!",
+        Key::GamesPlayed => "  games played:      {0}",
+        Key::GamesWon => "  won:               {0} ({1}%)",
+        Key::AverageGuesses => "  average guesses:   {0}",
+        Key::MedianGuesses => "  median guesses:    {0}",
+        Key::LongestStreak => "  longest streak:    {0}",
+        Key::GuessesPerGame => "  guesses per game",
+        Key::Lost => "lost",
+        Key::TuiTitle => "==== Number Guessing Game ====",
+        Key::TuiStillPossible => "still possible: {0} to {1}",
+        Key::TuiGuessesLeft => "Guesses left: {0}",
+        Key::TuiGuessesSoFar => "Guesses so far: {0}",
+        Key::TuiHistory => "History:",
+        Key::TuiNoGuesses => "(no guesses yet)",
+        Key::TuiTypeNumber => "Type a number and press Enter.",
+        Key::TuiTypeAnswer => "Type +, - or = and press Enter.",
+        Key::TuiGuessPrompt => "Your guess (q to quit): ",
+        Key::TuiJudgePrompt => "Is it {0}? +, - or = (q to quit): ",
+    }
+}
+
+fn spanish(key: Key) -> &'static str {
+    match key {
+        Key::Welcome => "==== ¡Bienvenido al juego de adivinar el número! ====",
+        Key::Difficulty => "Dificultad: {0}",
+        Key::Variant => "Reglas: {0}",
+        Key::Seed => "Semilla: {0}",
+        Key::TimeLimit => {
+            "Tienes {0} segundo por ronda - un número escrito después pierde la ronda.|Tienes {0} segundos por ronda - un número escrito después pierde la ronda."
+        }
+        Key::SpeedRun => "Contrarreloj: {0} ronda - ¡el reloj está en marcha!|Contrarreloj: {0} rondas seguidas - ¡el reloj está en marcha!",
+        Key::TypeQuit => "Escribe 'quit' o 'q' en cualquier momento para rendirte.",
+        Key::ThinkingOf => "Estoy pensando en un número entre {0} y {1}.",
+        Key::GuessesToFind => "Tienes {0} intento para encontrarlo.|Tienes {0} intentos para encontrarlo.",
+        Key::ComputerGuesses => "Tengo {0} intento para encontrarlo.|Tengo {0} intentos para encontrarlo.",
+        Key::RulesShrinking => {
+            "Estoy pensando en un número entre {0} y {1}.\nCada respuesta reduce el rango, y tienes que adivinar dentro de él."
+        }
+        Key::RulesSeveral => {
+            "Estoy pensando en {0} números distintos entre {1} y {2}. ¡Encuéntralos todos!\nSi fallas, te digo cuántos quedan por debajo y por encima."
+        }
+        Key::RulesReverse => {
+            "Piensa un número entre {0} y {1} y yo lo adivinaré.\nResponde + si tu número es mayor, - si es menor y = cuando acierte."
+        }
+        Key::SecondsLeft => "Queda {0} segundo.|Quedan {0} segundos.",
+        Key::EnterGuess => "Escribe tu número: ",
+        Key::JudgeGuess => "¿Es el {0}? (+ mayor, - menor, = sí): ",
+        Key::TimesUp => "¡Se acabó el tiempo! El número era {0}.",
+        Key::GiveUp => "¿Ya te rindes? El número era {0}.",
+        Key::YouGuessed => "Has dicho {0}",
+        Key::TooSmall => "Demasiado pequeño...",
+        Key::TooLarge => "Demasiado grande...",
+        Key::Correct => "¡Correcto!",
+        Key::Narrowed => "{0} está entre {1} y {2}.",
+        Key::FoundOne => "¡Encontraste uno! Quedan {0} escondidos.",
+        Key::FoundLast => "Encontraste uno - ¡y era el último!",
+        Key::Missed => "Fallaste - {0} por debajo, {1} por encima.",
+        Key::Congratulations => "¡Enhorabuena! Lo has adivinado en {0} intento.|¡Enhorabuena! Lo has adivinado en {0} intentos.",
+        Key::OutOfGuesses => "¡Sin intentos! El número era {0}.",
+        Key::ComputerGiveUp => "Vale - guárdate tu número.",
+        Key::ComputerFound => "¡Ya está! Encontré tu número en {0} intento.|¡Ya está! Encontré tu número en {0} intentos.",
+        Key::ComputerOutOfGuesses => "¡Me quedé sin intentos! Tu número está en {0}.",
+        Key::GuessesLeft => "Te queda {0} intento.|Te quedan {0} intentos.",
+        Key::NoMoreInput => "No hay más entrada - el número era {0}.",
+        Key::PlayAgain => "¿Jugar otra vez? (s/n): ",
+        Key::Yes => "s",
+        Key::EnterName => "Escribe tu nombre: ",
+        Key::InputEmpty => "Escribe un número (o 'quit' para rendirte).",
+        Key::InputNegative => "El número secreto nunca es negativo.",
+        Key::InputOutOfRange => "Tu número debe estar entre {0} y {1}.",
+        Key::InputOverflow => "Ese número es demasiado grande para ser el número secreto.",
+        Key::InputNotANumber => "'{0}' no es un número.",
+        Key::InputNotAnAnswer => "Responde + (tu número es mayor), - (menor) o = (¡es ese!).",
+        Key::InputContradiction => "Eso no puede ser - no encaja con tus respuestas anteriores.",
+        Key::Hint => "{0}.",
+        Key::HintTrend => "{0} - {1}.",
+        Key::Freezing => "Helado",
+        Key::Cold => "Frío",
+        Key::Warm => "Templado",
+        Key::Hot => "Caliente",
+        Key::Warmer => "más cerca que la última vez",
+        Key::Colder => "más lejos que la última vez",
+        Key::NoCloser => "igual de cerca que la última vez",
+        Key::SplitsTitle => "==== Contrarreloj ====",
+        Key::SplitRound => "  ronda {0}: {1}s",
+        Key::SplitFastest => "  mejor:     {0}s",
+        Key::SplitSlowest => "  peor:      {0}s",
+        Key::SplitTotal => "  total:     {0}s",
+        Key::CodeWelcome => "==== ¡Bienvenido a Toros y Vacas! ====",
+        Key::CodeRules => "Estoy pensando en un código de {0} cifras sin cifras repetidas.",
+        Key::CodeExplain => "Un toro es una cifra correcta en su sitio, una vaca es una cifra correcta en otro sitio.",
+        Key::Bulls => "{0} toro|{0} toros",
+        Key::Cows => "{0} vaca|{0} vacas",
+        Key::CodeGiveUp => "¿Ya te rindes? El código era {0}.",
+        Key::CodeCracked => "¡Enhorabuena! Descifraste el código en {0} intento.|¡Enhorabuena! Descifraste el código en {0} intentos.",
+        Key::CodeOutOfGuesses => "¡Sin intentos! El código era {0}.",
+        Key::CodeNoMoreInput => "No hay más entrada - el código era {0}.",
+        Key::CodeEmpty => "Escribe un código (o 'quit' para rendirte).",
+        Key::CodeWrongLength => "El código tiene {0} cifras, pero has escrito {1}.",
+        Key::CodeNotADigit => "'{0}' no es una cifra.",
+        Key::CodeRepeatedDigit => "La cifra {0} aparece más de una vez - todas las cifras son distintas.",
+        Key::Tolerance => "Vale cualquier número que esté a {0} o menos de él.",
+        Key::LyingWelcome => "==== ¡Bienvenido al juego de Ulam! ====",
+        Key::LyingRules => "Cuidado: hasta {0} de mis respuestas pueden ser mentira ({1}).",
+        Key::LyingNeverLies => "Nunca miento cuando dices el número exacto.",
+        Key::LiarRandom => "al azar ({0}% de probabilidad por respuesta)",
+        Key::LiarAdversarial => "adversario",
+        Key::LyingTooSmall => "Demasiado pequeño... (¿o no?)",
+        Key::LyingTooLarge => "Demasiado grande... (¿o no?)",
+        Key::LyingFound => "¡{1} es correcto, encontrado en {0} intento!|¡{1} es correcto, encontrado en {0} intentos!",
+        Key::TheNumberWas => "El número era {0}.",
+        Key::LiesTold => "Dije {0} mentira:|Dije {0} mentiras:",
+        Key::LieMark => "<- ¡mentira!",
+        Key::InputEnded => "No hay más entrada.",
+        Key::WordTooSmall => "demasiado pequeño",
+        Key::WordTooLarge => "demasiado grande",
+        Key::WordCorrect => "correcto",
+        Key::AutoTitle => "==== Juega el ordenador ({0}) ====",
+        Key::AutoRange => "El número está entre {0} y {1}.",
+        Key::ComputerGuessed => "El ordenador dijo {0} - {1}",
+        Key::AutoFound => "Encontró el {1} en {0} intento.|Encontró el {1} en {0} intentos.",
+        Key::AchievementUnlocked => "*** Logro desbloqueado: {0} ***",
+        Key::FirstTry => "Suerte de principiante - gana una ronda al primer intento",
+        Key::Optimal => "Cerebro binario - gana en menos intentos de los que podría necesitar la búsqueda binaria",
+        Key::Streak => "Racha imparable - gana {0} rondas seguidas",
+        Key::HardCleared => "Duro de pelar - gana una ronda en hard",
+        Key::NoProfile => "{0} todavía no tiene perfil - termina una ronda con --name {0} para empezar uno.",
+        Key::RoundsPlayed => "  rondas jugadas:    {0}",
+        Key::RoundsWon => "  rondas ganadas:    {0}",
+        Key::CurrentStreak => "  racha actual:      {0}",
+        Key::BestStreak => "  mejor racha:       {0}",
+        Key::Wins => "{0} victoria|{0} victorias",
+        Key::Achievements => "  logros ({0} de {1})",
+        Key::HighScores => "==== Mejores puntuaciones ====",
+        Key::ScoresHeader => "  jugador               rango intentos    tiempo  fecha",
+        Key::NoWins => "Todavía no se ha ganado ninguna partida - ¡ve a jugar una!",
+        Key::Skipped => "(se ignoró {0} línea ilegible de {1})|(se ignoraron {0} líneas ilegibles de {1})",
+        Key::StatsTitle => "==== Estadísticas ({0}) ====",
+        Key::AllRounds => "todas las rondas",
+        Key::StatsFrom => "desde {0}",
+        Key::StatsTo => "hasta {0}",
+        Key::NoRounds => "No hay ninguna ronda - ¡ve a jugar una!",
+        Key::GamesPlayed => "  partidas jugadas:  {0}",
+        Key::GamesWon => "  ganadas:           {0} ({1}%)",
+        Key::AverageGuesses => "  intentos de media: {0}",
+        Key::MedianGuesses => "  mediana:           {0}",
+        Key::LongestStreak => "  racha más larga:   {0}",
+        Key::GuessesPerGame => "  intentos por partida",
+        Key::Lost => "perdidas",
+        Key::TuiTitle => "==== Adivina el número ====",
+        Key::TuiStillPossible => "todavía posible: de {0} a {1}",
+        Key::TuiGuessesLeft => "Intentos restantes: {0}",
+        Key::TuiGuessesSoFar => "Intentos hasta ahora: {0}",
+        Key::TuiHistory => "Historial:",
+        Key::TuiNoGuesses => "(todavía ningún intento)",
+        Key::TuiTypeNumber => "Escribe un número y pulsa Intro.",
+        Key::TuiTypeAnswer => "Escribe +, - o = y pulsa Intro.",
+        Key::TuiGuessPrompt => "Tu número (q para salir): ",
+        Key::TuiJudgePrompt => "¿Es el {0}? +, - o = (q para salir): ",
+    }
+}
+
+// the messages for one locale
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Catalog {
+    locale: Locale,
+}
+
+impl Catalog {
+    pub fn new(locale: Locale) -> Catalog {
+        Catalog { locale }
+    }
+
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    // the raw template for a key, falling back to English when the locale does not have it
+    pub fn template(&self, key: Key) -> &'static str {
+        match self.locale {
+            Locale::English => english(key),
+            Locale::Spanish => spanish(key),
+        }
+    }
+
+    // a message with its placeholders filled in, e.g. text(Key::ThinkingOf, &[&1, &100])
+    // &dyn fmt::Display lets the list mix numbers, strings and anything else printable
+    pub fn text(&self, key: Key, args: &[&dyn fmt::Display]) -> String {
+        fill(self.template(key), args)
+    }
+
+    // a message about a count: picks the singular or plural form and puts the count in {0}
+    pub fn count(&self, key: Key, count: u32) -> String {
+        self.count_with(key, count, &[])
+    }
+
+    // the same, with more arguments after the count in {1}, {2} ...
+    pub fn count_with(&self, key: Key, count: u32, args: &[&dyn fmt::Display]) -> String {
+        let template = self.template(key);
+        // split_once gives the text either side of the first '|', if there is one
+        let form = match template.split_once('|') {
+            Some((one, _)) if count == 1 => one,
+            Some((_, many)) => many,
+            None => template,
+        };
+        let mut all: Vec<&dyn fmt::Display> = vec![&count];
+        all.extend_from_slice(args);
+        fill(form, &all)
+    }
+
+    // does the player's answer mean yes?
+    pub fn is_yes(&self, answer: &str) -> bool {
+        let answer = answer.trim();
+        // "y" is always understood, whatever the language
+        answer.eq_ignore_ascii_case(self.template(Key::Yes)) || answer.eq_ignore_ascii_case("y")
+    }
+
+    // the start of a round of any variant
+    pub fn rules(&self, rules: &Rules) -> String {
+        match *rules {
+            Rules::OneNumber { low, high } => self.text(Key::ThinkingOf, &[&low, &high]),
+            Rules::Shrinking { low, high } => self.text(Key::RulesShrinking, &[&low, &high]),
+            Rules::Several { count, low, high } => self.text(Key::RulesSeveral, &[&count, &low, &high]),
+            Rules::Reverse { low, high } => self.text(Key::RulesReverse, &[&low, &high]),
+        }
+    }
+
+    pub fn prompt(&self, prompt: &Prompt) -> String {
+        match *prompt {
+            Prompt::Guess => self.text(Key::EnterGuess, &[]),
+            Prompt::Judge(guess) => self.text(Key::JudgeGuess, &[&guess]),
+        }
+    }
+
+    pub fn outcome(&self, outcome: Outcome) -> String {
+        match outcome {
+            Outcome::TooSmall => self.text(Key::TooSmall, &[]),
+            Outcome::TooLarge => self.text(Key::TooLarge, &[]),
+            Outcome::Correct => self.text(Key::Correct, &[]),
+        }
+    }
+
+    pub fn feedback(&self, feedback: &Feedback) -> String {
+        match *feedback {
+            Feedback::Compare(outcome) | Feedback::Narrowed { outcome: outcome @ Outcome::Correct, .. } => {
+                self.outcome(outcome)
+            }
+            Feedback::Narrowed { outcome, low, high } => self.text(Key::Narrowed, &[&self.outcome(outcome), &low, &high]),
+            Feedback::Found { remaining: 0 } => self.text(Key::FoundLast, &[]),
+            Feedback::Found { remaining } => self.text(Key::FoundOne, &[&remaining]),
+            Feedback::Missed { below, above } => self.text(Key::Missed, &[&below, &above]),
+        }
+    }
+
+    // the outcome in lower case, for lists of guesses
+    pub fn outcome_word(&self, outcome: Outcome) -> String {
+        match outcome {
+            Outcome::TooSmall => self.text(Key::WordTooSmall, &[]),
+            Outcome::TooLarge => self.text(Key::WordTooLarge, &[]),
+            Outcome::Correct => self.text(Key::WordCorrect, &[]),
+        }
+    }
+
+    pub fn hint(&self, hint: &Hint) -> String {
+        let band = match hint.band {
+            Band::Freezing => self.text(Key::Freezing, &[]),
+            Band::Cold => self.text(Key::Cold, &[]),
+            Band::Warm => self.text(Key::Warm, &[]),
+            Band::Hot => self.text(Key::Hot, &[]),
+        };
+        let trend = match hint.trend {
+            Some(Trend::Warmer) => Key::Warmer,
+            Some(Trend::Colder) => Key::Colder,
+            Some(Trend::Same) => Key::NoCloser,
+            None => return self.text(Key::Hint, &[&band]),
+        };
+        self.text(Key::HintTrend, &[&band, &self.text(trend, &[])])
+    }
+
+    // a Bulls and Cows score: "1 bull, 2 cows"
+    pub fn score(&self, score: &Score) -> String {
+        format!("{}, {}", self.count(Key::Bulls, score.bulls), self.count(Key::Cows, score.cows))
+    }
+
+    pub fn code_error(&self, error: &CodeError) -> String {
+        match *error {
+            CodeError::Empty => self.text(Key::CodeEmpty, &[]),
+            CodeError::WrongLength { expected, got } => self.text(Key::CodeWrongLength, &[&expected, &got]),
+            CodeError::NotADigit(c) => self.text(Key::CodeNotADigit, &[&c]),
+            CodeError::RepeatedDigit(c) => self.text(Key::CodeRepeatedDigit, &[&c]),
+        }
+    }
+
+    pub fn liar(&self, policy: LiePolicy) -> String {
+        match policy {
+            LiePolicy::Random(chance) => self.text(Key::LiarRandom, &[&format!("{:.0}", chance * 100.0)]),
+            LiePolicy::Adversarial => self.text(Key::LiarAdversarial, &[]),
+        }
+    }
+
+    // an achievement's title and what it takes
+    pub fn achievement(&self, achievement: Achievement) -> String {
+        match achievement {
+            Achievement::FirstTry => self.text(Key::FirstTry, &[]),
+            Achievement::Optimal => self.text(Key::Optimal, &[]),
+            Achievement::Streak => self.text(Key::Streak, &[&STREAK_GOAL]),
+            Achievement::HardCleared => self.text(Key::HardCleared, &[]),
+        }
+    }
+
+    pub fn input_error(&self, error: &InputError) -> String {
+        match *error {
+            InputError::Empty => self.text(Key::InputEmpty, &[]),
+            InputError::Negative => self.text(Key::InputNegative, &[]),
+            InputError::OutOfRange { ref low, ref high } => self.text(Key::InputOutOfRange, &[low, high]),
+            InputError::Overflow => self.text(Key::InputOverflow, &[]),
+            InputError::NotANumber(ref text) => self.text(Key::InputNotANumber, &[text]),
+            InputError::NotAnAnswer => self.text(Key::InputNotAnAnswer, &[]),
+            InputError::Contradiction => self.text(Key::InputContradiction, &[]),
+        }
+    }
+}
+
+// replace {0}, {1}, ... in a template with the matching argument
+// this is a single pass over the template, so an argument that itself contains "{1}" is left exactly as it is
+fn fill(template: &str, args: &[&dyn fmt::Display]) -> String {
+    let mut result = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        // a number between braces with an argument to go with it - anything else is copied as it is
+        let placeholder = after.find('}').and_then(|end| {
+            let index: usize = after[..end].parse().ok()?;
+            args.get(index).map(|arg| (end, arg))
+        });
+        match placeholder {
+            Some((end, arg)) => {
+                result.push_str(&arg.to_string());
+                rest = &after[end + 1..];
+            }
+            None => {
+                result.push('{');
+                rest = after;
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // every Key - the match in english() and spanish() has no wildcard, so a new Key can't be left untranslated,
+    // but it does have to be added here to have its placeholders checked
+    const KEYS: [Key; 128] = [
+        Key::Welcome, Key::Difficulty, Key::Variant, Key::Seed, Key::TimeLimit, Key::SpeedRun, Key::TypeQuit,
+        Key::ThinkingOf, Key::GuessesToFind, Key::ComputerGuesses, Key::RulesShrinking, Key::RulesSeveral,
+        Key::RulesReverse, Key::SecondsLeft, Key::EnterGuess, Key::JudgeGuess, Key::TimesUp, Key::GiveUp,
+        Key::YouGuessed, Key::TooSmall, Key::TooLarge, Key::Correct, Key::Narrowed, Key::FoundOne, Key::FoundLast,
+        Key::Missed, Key::Congratulations, Key::OutOfGuesses, Key::ComputerGiveUp, Key::ComputerFound,
+        Key::ComputerOutOfGuesses, Key::GuessesLeft, Key::NoMoreInput, Key::PlayAgain, Key::Yes, Key::EnterName,
+        Key::InputEmpty, Key::InputNegative, Key::InputOutOfRange, Key::InputOverflow, Key::InputNotANumber,
+        Key::InputNotAnAnswer, Key::InputContradiction, Key::Hint, Key::HintTrend, Key::Freezing, Key::Cold, Key::Warm,
+        Key::Hot, Key::Warmer, Key::Colder, Key::NoCloser, Key::SplitsTitle, Key::SplitRound, Key::SplitFastest,
+        Key::SplitSlowest, Key::SplitTotal, Key::CodeWelcome, Key::CodeRules, Key::CodeExplain, Key::Bulls, Key::Cows,
+        Key::CodeGiveUp, Key::CodeCracked, Key::CodeOutOfGuesses, Key::CodeNoMoreInput, Key::CodeEmpty,
+        Key::CodeWrongLength, Key::CodeNotADigit, Key::CodeRepeatedDigit, Key::Tolerance, Key::LyingWelcome,
+        Key::LyingRules, Key::LyingNeverLies, Key::LiarRandom, Key::LiarAdversarial, Key::LyingTooSmall,
+        Key::LyingTooLarge, Key::LyingFound, Key::TheNumberWas, Key::LiesTold, Key::LieMark, Key::InputEnded,
+        Key::WordTooSmall, Key::WordTooLarge, Key::WordCorrect, Key::AutoTitle, Key::AutoRange, Key::ComputerGuessed,
+        Key::AutoFound, Key::AchievementUnlocked, Key::FirstTry, Key::Optimal, Key::Streak, Key::HardCleared,
+        Key::NoProfile, Key::RoundsPlayed, Key::RoundsWon, Key::CurrentStreak, Key::BestStreak, Key::Wins,
+        Key::Achievements, Key::HighScores, Key::ScoresHeader, Key::NoWins, Key::Skipped, Key::StatsTitle,
+        Key::AllRounds, Key::StatsFrom, Key::StatsTo, Key::NoRounds, Key::GamesPlayed, Key::GamesWon,
+        Key::AverageGuesses, Key::MedianGuesses, Key::LongestStreak, Key::GuessesPerGame, Key::Lost, Key::TuiTitle,
+        Key::TuiStillPossible, Key::TuiGuessesLeft, Key::TuiGuessesSoFar, Key::TuiHistory, Key::TuiNoGuesses,
+        Key::TuiTypeNumber, Key::TuiTypeAnswer, Key::TuiGuessPrompt, Key::TuiJudgePrompt,
+    ];
+
+    // the placeholders a template uses, sorted, e.g. [0, 1] for "between {0} and {1}"
+    fn placeholders(template: &str) -> Vec<String> {
+        let mut found: Vec<String> = template
+            .split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}').map(|(inside, _)| inside.to_string()))
+            .collect();
+        found.sort();
+        found.dedup();
+        found
+    }
+
+    #[test]
+    fn every_key_is_translated_with_the_same_placeholders() {
+        for &key in KEYS.iter() {
+            let (english, spanish) = (english(key), spanish(key));
+            assert!(!spanish.trim().is_empty(), "{:?} has no Spanish text", key);
+            assert_eq!(placeholders(english), placeholders(spanish), "{:?}", key);
+            // a message with a plural form needs one in every language
+            assert_eq!(english.contains('|'), spanish.contains('|'), "{:?}", key);
+        }
+    }
+
+    #[test]
+    fn fill_puts_each_argument_in_its_place() {
+        assert_eq!(fill("between {0} and {1}.", &[&1, &100]), "between 1 and 100.");
+        // a translation can use the arguments in any order, and more than once
+        assert_eq!(fill("{1} {0} {1}", &[&"a", &"b"]), "b a b");
+        // a placeholder with no argument, or braces around something else, stay as they are
+        assert_eq!(fill("{0} {1} {x} {} {", &[&7]), "7 {1} {x} {} {");
+    }
+
+    #[test]
+    fn fill_never_fills_in_an_argument() {
+        assert_eq!(fill("'{0}' is not a number, {1}", &[&"{1}", &"sorry"]), "'{1}' is not a number, sorry");
+        assert_eq!(fill("{0}{1}", &[&"{", &"0}"]), "{0}");
+
+        let typed = InputError::NotANumber(String::from("{0}{1}"));
+        assert_eq!(Catalog::new(Locale::English).input_error(&typed), "'{0}{1}' is not a number.");
+        assert_eq!(Catalog::new(Locale::Spanish).input_error(&typed), "'{0}{1}' no es un número.");
+    }
+
+    #[test]
+    fn counts_pick_the_singular_or_the_plural() {
+        let catalog = Catalog::new(Locale::English);
+        assert_eq!(catalog.count(Key::SecondsLeft, 1), "1 second left.");
+        assert_eq!(catalog.count(Key::SecondsLeft, 0), "0 seconds left.");
+        assert_eq!(Catalog::new(Locale::Spanish).count(Key::SecondsLeft, 2), "Quedan 2 segundos.");
+    }
+
+    #[test]
+    fn locale_tags() {
+        assert_eq!(Locale::from_tag("es_ES.UTF-8"), Some(Locale::Spanish));
+        assert_eq!(Locale::from_tag("en-GB"), Some(Locale::English));
+        assert_eq!(Locale::from_tag("C"), Some(Locale::English));
+        assert_eq!(Locale::from_tag("fr_FR"), None);
+        assert_eq!(Locale::from_tag(""), None);
+        assert!("de".parse::<Locale>().is_err());
+    }
+}
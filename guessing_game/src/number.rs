@@ -0,0 +1,69 @@
+// the number types the game can be played with
+// Game, Config and the input parser are generic over Number, with u32 (the original game) as the default
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+// SampleUniform is what rand needs to pick a random value between two bounds
+use rand::distributions::uniform::SampleUniform;
+
+pub trait Number: Copy + PartialOrd + fmt::Display + fmt::Debug + FromStr + SampleUniform {
+    // can the type hold negative numbers? if not, "-5" gets the 'never negative' message
+    const SIGNED: bool;
+
+    // zero - the tolerance for exact types
+    fn zero() -> Self;
+
+    // compare a guess with the secret
+    // a guess within `tolerance` of the secret counts as Equal (only floating point games use a tolerance)
+    fn compare(guess: Self, secret: Self, tolerance: Self) -> Ordering;
+
+    // false for values like NaN and infinity that parse() accepts but cannot be a secret number
+    fn is_finite(self) -> bool {
+        true
+    }
+}
+
+// the integer types all work the same way, so a macro writes the impl for each of them
+// tolerance is ignored: two integers are either equal or not
+macro_rules! integer_number {
+    ($($t:ty => $signed:expr),*) => {
+        $(
+            impl Number for $t {
+                const SIGNED: bool = $signed;
+
+                fn zero() -> $t {
+                    0
+                }
+
+                fn compare(guess: $t, secret: $t, _tolerance: $t) -> Ordering {
+                    guess.cmp(&secret)
+                }
+            }
+        )*
+    };
+}
+
+integer_number!(u32 => false, u64 => false, i64 => true);
+
+impl Number for f64 {
+    const SIGNED: bool = true;
+
+    fn zero() -> f64 {
+        0.0
+    }
+
+    fn compare(guess: f64, secret: f64, tolerance: f64) -> Ordering {
+        if (guess - secret).abs() <= tolerance {
+            Ordering::Equal
+        } else if guess < secret {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        }
+    }
+
+    fn is_finite(self) -> bool {
+        f64::is_finite(self)
+    }
+}
@@ -0,0 +1,347 @@
+// named player profiles: a running record of each player's games and the achievements they have unlocked
+//
+// profiles are kept in a plain text file, one player per line with the fields separated by tabs:
+//   name  played  won  current streak  best streak  achievements (comma separated)
+// the whole file is rewritten after every round - lines that can't be read are written back exactly as they were,
+// so a damaged line never costs anybody their profile
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::config::Difficulty;
+use crate::scores::{self, ScoreEntry};
+use crate::solver;
+
+// how many wins in a row the streak achievement needs
+pub const STREAK_GOAL: u32 = 10;
+
+// deriving Ord lets achievements live in a BTreeSet, which keeps them in the order they are declared here
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Achievement {
+    // guessed the number with the very first guess
+    FirstTry,
+    // won in fewer guesses than binary search could need for the range
+    Optimal,
+    // won STREAK_GOAL rounds in a row
+    Streak,
+    // won a round on hard
+    HardCleared,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 4] = [
+        Achievement::FirstTry,
+        Achievement::Optimal,
+        Achievement::Streak,
+        Achievement::HardCleared,
+    ];
+
+    // the name used in the profile file
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Achievement::FirstTry => "first-try",
+            Achievement::Optimal => "optimal",
+            Achievement::Streak => "streak",
+            Achievement::HardCleared => "hard-cleared",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match *self {
+            Achievement::FirstTry => "Beginner's Luck",
+            Achievement::Optimal => "Binary Brain",
+            Achievement::Streak => "On a Roll",
+            Achievement::HardCleared => "Hard as Nails",
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match *self {
+            Achievement::FirstTry => String::from("win a round with your first guess"),
+            Achievement::Optimal => String::from("win in fewer guesses than binary search could need"),
+            Achievement::Streak => format!("win {} rounds in a row", STREAK_GOAL),
+            Achievement::HardCleared => String::from("win a round on hard"),
+        }
+    }
+}
+
+impl fmt::Display for Achievement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} - {}", self.title(), self.description())
+    }
+}
+
+impl FromStr for Achievement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Achievement, String> {
+        Achievement::ALL
+            .iter()
+            .find(|achievement| achievement.name() == s)
+            .cloned()
+            .ok_or_else(|| format!("unknown achievement '{}'", s))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+    pub name: String,
+    pub played: u32,
+    pub won: u32,
+    // wins in a row up to now, and the most ever
+    pub streak: u32,
+    pub best_streak: u32,
+    pub achievements: BTreeSet<Achievement>,
+}
+
+impl Profile {
+    pub fn new(name: &str) -> Profile {
+        Profile {
+            name: name.to_string(),
+            ..Profile::default()
+        }
+    }
+
+    // add a finished round to the profile and return any achievements it unlocked for the first time
+    pub fn record(&mut self, entry: &ScoreEntry) -> Vec<Achievement> {
+        self.played += 1;
+        if !entry.won {
+            self.streak = 0;
+            return Vec::new();
+        }
+
+        self.won += 1;
+        self.streak += 1;
+        self.best_streak = self.best_streak.max(self.streak);
+
+        let earned = [
+            (Achievement::FirstTry, entry.attempts == 1),
+            // the presets give exactly worst_case guesses (10 for 1 to 1000), so "no more than" would be every win
+            (Achievement::Optimal, entry.attempts < solver::worst_case(entry.low, entry.high)),
+            (Achievement::Streak, self.streak >= STREAK_GOAL),
+            (Achievement::HardCleared, entry.difficulty == Difficulty::Hard),
+        ];
+
+        // insert() returns true only when the achievement was not already in the set
+        earned
+            .iter()
+            .filter(|&&(achievement, done)| done && self.achievements.insert(achievement))
+            .map(|&(achievement, _)| achievement)
+            .collect()
+    }
+
+    fn to_line(&self) -> String {
+        let achievements: Vec<&str> = self.achievements.iter().map(|a| a.name()).collect();
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            self.name,
+            self.played,
+            self.won,
+            self.streak,
+            self.best_streak,
+            achievements.join(",")
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Profile> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 6 || fields[0].is_empty() {
+            return None;
+        }
+
+        let achievements = if fields[5].is_empty() {
+            BTreeSet::new()
+        } else {
+            // collect() can gather Results too - the first error stops it
+            fields[5].split(',').map(|name| name.parse()).collect::<Result<_, _>>().ok()?
+        };
+
+        Some(Profile {
+            name: fields[0].to_string(),
+            played: fields[1].parse().ok()?,
+            won: fields[2].parse().ok()?,
+            streak: fields[3].parse().ok()?,
+            best_streak: fields[4].parse().ok()?,
+            achievements,
+        })
+    }
+}
+
+// every profile in the file
+#[derive(Debug, Default)]
+pub struct Profiles {
+    pub profiles: Vec<Profile>,
+    // lines that could not be understood, as raw bytes because they may not even be UTF-8
+    pub unreadable: Vec<Vec<u8>>,
+}
+
+impl Profiles {
+    // names are matched ignoring case, so "Alice" and "alice" share a profile
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|profile| profile.name.eq_ignore_ascii_case(name))
+    }
+
+    // the profile for this name, starting a new one if there isn't one yet
+    pub fn get_or_create(&mut self, name: &str) -> &mut Profile {
+        let index = match self.profiles.iter().position(|profile| profile.name.eq_ignore_ascii_case(name)) {
+            Some(index) => index,
+            None => {
+                self.profiles.push(Profile::new(name));
+                self.profiles.len() - 1
+            }
+        };
+        &mut self.profiles[index]
+    }
+}
+
+// where profiles are kept when --profile-file is not given
+pub fn default_path() -> PathBuf {
+    scores::home_path(".guessing_game_profiles")
+}
+
+// read every profile - a missing file just means nobody has a profile yet
+pub fn load(path: &Path) -> io::Result<Profiles> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Profiles::default()),
+        Err(e) => return Err(e),
+    };
+
+    let mut profiles = Profiles::default();
+    // split() hands over the bytes of each line, where lines() would fail on one that is not UTF-8
+    for line in BufReader::new(file).split(b'\n') {
+        let line = line?;
+        let text = String::from_utf8(line.clone()).ok();
+        if text.as_deref().is_some_and(|text| text.trim().is_empty()) {
+            continue;
+        }
+
+        match text.as_deref().and_then(Profile::from_line) {
+            Some(profile) => profiles.profiles.push(profile),
+            None => profiles.unreadable.push(line),
+        }
+    }
+
+    Ok(profiles)
+}
+
+// write every profile back to the file
+// the new contents go to a temporary file first and are then renamed over the old one,
+// so a crash half way through can never leave a half-written profile file behind
+pub fn save(path: &Path, profiles: &Profiles) -> io::Result<()> {
+    let mut contents = Vec::new();
+    for profile in &profiles.profiles {
+        writeln!(contents, "{}", profile.to_line())?;
+    }
+    for line in &profiles.unreadable {
+        contents.extend_from_slice(line);
+        contents.push(b'\n');
+    }
+
+    let temporary = path.with_extension("tmp");
+    fs::write(&temporary, contents)?;
+    fs::rename(&temporary, path)
+}
+
+// add a finished round to the player's profile and save it, returning the newly unlocked achievements
+pub fn record(path: &Path, entry: &ScoreEntry) -> io::Result<Vec<Achievement>> {
+    let mut profiles = load(path)?;
+    let unlocked = profiles.get_or_create(&scores::clean_name(&entry.player)).record(entry);
+    save(path, &profiles)?;
+    Ok(unlocked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(player: &str) -> ScoreEntry {
+        ScoreEntry {
+            player: player.to_string(),
+            difficulty: Difficulty::Normal,
+            low: 1,
+            high: 100,
+            attempts: 5,
+            won: true,
+            duration: std::time::Duration::from_secs(10),
+            finished_at: 0,
+        }
+    }
+
+    #[test]
+    fn recording_a_round_keeps_unreadable_lines() {
+        let path = std::env::temp_dir().join(format!("guessing_game_profiles_{}", std::process::id()));
+        let mut damaged = b"bob\t3\t2\t1\t2\t".to_vec();
+        damaged.extend_from_slice(b"\nnot a profile\n\xff\xfe\n");
+        fs::write(&path, &damaged).unwrap();
+
+        record(&path, &entry("alice")).unwrap();
+        let loaded = load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.get("alice").map(|profile| profile.won), Some(1));
+        assert_eq!(loaded.get("bob").map(|profile| profile.played), Some(3));
+        assert_eq!(loaded.unreadable, vec![b"not a profile".to_vec(), vec![0xff, 0xfe]]);
+    }
+
+    fn round(difficulty: Difficulty, high: u32, attempts: u32, won: bool) -> ScoreEntry {
+        ScoreEntry { difficulty, high, attempts, won, ..entry("alice") }
+    }
+
+    #[test]
+    fn a_first_guess_win_is_also_optimal() {
+        let mut profile = Profile::new("alice");
+        assert_eq!(
+            profile.record(&round(Difficulty::Normal, 100, 1, true)),
+            vec![Achievement::FirstTry, Achievement::Optimal]
+        );
+        // each achievement is only announced the first time
+        assert_eq!(profile.record(&round(Difficulty::Normal, 100, 1, true)), vec![]);
+        assert_eq!((profile.played, profile.won), (2, 2));
+    }
+
+    #[test]
+    fn optimal_means_beating_binary_search() {
+        // 1 to 100 takes binary search up to 7 guesses, and 1 to 1000 up to 10
+        let mut profile = Profile::new("alice");
+        assert_eq!(profile.record(&round(Difficulty::Normal, 100, 7, true)), vec![]);
+        assert_eq!(profile.record(&round(Difficulty::Normal, 100, 6, true)), vec![Achievement::Optimal]);
+
+        // using every guess the hard preset gives clears hard, but is no better than binary search
+        let mut profile = Profile::new("bob");
+        assert_eq!(profile.record(&round(Difficulty::Hard, 1000, 10, true)), vec![Achievement::HardCleared]);
+        assert_eq!(profile.record(&round(Difficulty::Hard, 1000, 9, true)), vec![Achievement::Optimal]);
+    }
+
+    #[test]
+    fn a_loss_resets_the_streak_but_not_the_best() {
+        let mut profile = Profile::new("alice");
+        for _ in 1..STREAK_GOAL {
+            assert_eq!(profile.record(&round(Difficulty::Normal, 100, 8, true)), vec![]);
+        }
+        assert_eq!(profile.record(&round(Difficulty::Normal, 100, 8, true)), vec![Achievement::Streak]);
+        assert_eq!((profile.streak, profile.best_streak), (STREAK_GOAL, STREAK_GOAL));
+
+        // a lost round counts as played, unlocks nothing and starts the streak again
+        assert_eq!(profile.record(&round(Difficulty::Hard, 1000, 1, false)), vec![]);
+        assert_eq!((profile.played, profile.won), (STREAK_GOAL + 1, STREAK_GOAL));
+        assert_eq!((profile.streak, profile.best_streak), (0, STREAK_GOAL));
+
+        profile.record(&round(Difficulty::Normal, 100, 8, true));
+        assert_eq!((profile.streak, profile.best_streak), (1, STREAK_GOAL));
+    }
+
+    #[test]
+    fn profiles_read_back_what_they_write() {
+        let mut profile = Profile::new("alice");
+        profile.record(&round(Difficulty::Hard, 1000, 1, true));
+        profile.record(&round(Difficulty::Normal, 100, 8, false));
+
+        assert_eq!(Profile::from_line(&profile.to_line()), Some(profile));
+        assert_eq!(Profile::from_line("alice\t1\t1\t1\t1\tlucky"), None);
+        assert_eq!(Profile::from_line("\t1\t1\t1\t1\t"), None);
+    }
+}
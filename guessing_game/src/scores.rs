@@ -0,0 +1,351 @@
+// the score file is plain text with one finished round per line and the fields separated by tabs
+// keeping it human readable means a broken line can be spotted (and skipped) instead of crashing the game
+use std::env;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::Difficulty;
+
+// how many rounds are shown for each difficulty by the --scores view
+pub const TOP_N: usize = 5;
+
+// one finished round
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoreEntry {
+    pub player: String,
+    pub difficulty: Difficulty,
+    pub low: u32,
+    pub high: u32,
+    pub attempts: u32,
+    pub won: bool,
+    pub duration: Duration,
+    // when the round finished, in seconds since 1970-01-01 (the unix epoch)
+    pub finished_at: u64,
+}
+
+// everything that could be read back from the score file
+#[derive(Debug, Default)]
+pub struct Scores {
+    pub entries: Vec<ScoreEntry>,
+    // lines that could not be understood - they are left in the file but otherwise ignored
+    pub skipped: usize,
+}
+
+// where scores are kept when --score-file is not given: a hidden file in the home directory,
+// or the current directory if HOME is not set
+pub fn default_path() -> PathBuf {
+    home_path(".guessing_game_scores")
+}
+
+// a file in the home directory, or in the current directory if HOME is not set
+pub fn home_path(file: &str) -> PathBuf {
+    match env::var_os("HOME") {
+        Some(home) => Path::new(&home).join(file),
+        None => PathBuf::from(file),
+    }
+}
+
+// seconds since the unix epoch, for stamping a finished round
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl ScoreEntry {
+    // turn the entry into one line of the score file (without the trailing \n)
+    pub fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            clean_name(&self.player),
+            self.difficulty,
+            self.low,
+            self.high,
+            self.attempts,
+            if self.won { "won" } else { "lost" },
+            self.duration.as_millis(),
+            self.finished_at
+        )
+    }
+
+    // the opposite of to_line - None means the line is corrupt
+    pub fn from_line(line: &str) -> Option<ScoreEntry> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 8 {
+            return None;
+        }
+
+        let won = match fields[5] {
+            "won" => true,
+            "lost" => false,
+            _ => return None,
+        };
+
+        // .ok()? turns a failed parse into an early return of None
+        Some(ScoreEntry {
+            player: fields[0].to_string(),
+            difficulty: fields[1].parse().ok()?,
+            low: fields[2].parse().ok()?,
+            high: fields[3].parse().ok()?,
+            attempts: fields[4].parse().ok()?,
+            won,
+            duration: Duration::from_millis(fields[6].parse().ok()?),
+            finished_at: fields[7].parse().ok()?,
+        })
+    }
+}
+
+// tabs and newlines would break the file format, so they are replaced with spaces
+pub fn clean_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c == '\t' || c == '\n' || c == '\r' { ' ' } else { c })
+        .collect();
+    let name = name.trim();
+
+    if name.is_empty() {
+        String::from("anonymous")
+    } else {
+        name.to_string()
+    }
+}
+
+// read every entry from the score file
+// a missing file just means nobody has played yet, so it gives an empty list rather than an error
+pub fn load(path: &Path) -> io::Result<Scores> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Scores::default()),
+        Err(e) => return Err(e),
+    };
+
+    let mut scores = Scores::default();
+    for line in BufReader::new(file).lines() {
+        let line = match line {
+            Ok(line) => line,
+            // invalid UTF-8 and the like - count it as corrupt and carry on
+            Err(_) => {
+                scores.skipped += 1;
+                continue;
+            }
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match ScoreEntry::from_line(&line) {
+            Some(entry) => scores.entries.push(entry),
+            None => scores.skipped += 1,
+        }
+    }
+
+    Ok(scores)
+}
+
+// add one entry to the end of the score file, creating the file if needed
+// if the last write was cut off part way through a line, the new entry starts on a line of its own -
+// otherwise it would be glued onto the broken line and skipped along with it
+pub fn append(path: &Path, entry: &ScoreEntry) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).read(true).append(true).open(path)?;
+
+    let mut last = [b'\n'];
+    if file.metadata()?.len() > 0 {
+        file.seek(SeekFrom::End(-1))?;
+        file.read_exact(&mut last)?;
+    }
+    // in append mode every write goes to the end of the file, wherever the seek left us
+    if last[0] != b'\n' {
+        writeln!(file)?;
+    }
+    writeln!(file, "{}", entry.to_line())
+}
+
+// the best won rounds for one difficulty: fewest attempts first, then the quickest
+pub fn best(entries: &[ScoreEntry], difficulty: Difficulty, limit: usize) -> Vec<&ScoreEntry> {
+    let mut wins: Vec<&ScoreEntry> = entries
+        .iter()
+        .filter(|entry| entry.won && entry.difficulty == difficulty)
+        .collect();
+
+    wins.sort_by_key(|entry| (entry.attempts, entry.duration));
+    wins.truncate(limit);
+    wins
+}
+
+// format seconds since the unix epoch as a YYYY-MM-DD date (in UTC)
+pub fn format_date(secs: u64) -> String {
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// turn a count of days since 1970-01-01 into a (year, month, day) date
+// this is Howard Hinnant's days-to-civil algorithm, which works in 400 year 'eras'
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+// the opposite of civil_from_days: the number of days from 1970-01-01 to a (year, month, day) date
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let yoe = year - era * 400;
+    let mp = i64::from(if month > 2 { month - 3 } else { month + 9 });
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146_097 + doe - 719_468
+}
+
+// read a YYYY-MM-DD date (in UTC) as the seconds since the unix epoch at the start of that day
+// None for anything that is not a real date from 1970-01-01 to 9999-12-31
+pub fn parse_date(text: &str) -> Option<u64> {
+    let parts: Vec<&str> = text.trim().split('-').collect();
+    if parts.len() != 3 {
+        return None;
+    }
+
+    let year: i64 = parts[0].parse().ok()?;
+    let month: u32 = parts[1].parse().ok()?;
+    let day: u32 = parts[2].parse().ok()?;
+
+    // a four-digit year keeps the arithmetic below far away from overflowing
+    if !(1970..=9999).contains(&year) {
+        return None;
+    }
+
+    // a date like 2023-02-30 rolls over into March, so check that it comes back unchanged
+    let days = days_from_civil(year, month, day);
+    if civil_from_days(days) != (year, month, day) {
+        return None;
+    }
+
+    // from 1970 on the day count is never negative
+    (days as u64).checked_mul(86_400)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_dates_from_the_epoch_on() {
+        assert_eq!(parse_date("1970-01-01"), Some(0));
+        assert_eq!(parse_date("2024-02-29"), Some(1_709_164_800));
+        assert_eq!(parse_date("9999-12-31"), Some(253_402_214_400));
+    }
+
+    #[test]
+    fn refuses_dates_that_are_not_real_or_out_of_range() {
+        assert_eq!(parse_date("2023-02-30"), None);
+        assert_eq!(parse_date("1969-12-31"), None);
+        assert_eq!(parse_date("10000-01-01"), None);
+        assert_eq!(parse_date("99999999999999-01-01"), None);
+        assert_eq!(parse_date("2024-01"), None);
+    }
+
+    fn entry(player: &str, difficulty: Difficulty, attempts: u32, secs: u64, won: bool) -> ScoreEntry {
+        ScoreEntry {
+            player: player.to_string(),
+            difficulty,
+            low: 1,
+            high: 100,
+            attempts,
+            won,
+            duration: Duration::from_millis(secs * 1000 + 250),
+            finished_at: 1_709_164_800 + secs,
+        }
+    }
+
+    // a score file of its own for each test, so they can run at the same time
+    fn temp_file(test: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("guessing_game_scores_{}_{}", test, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn entries_saved_to_a_file_load_back_the_same() {
+        let path = temp_file("round_trip");
+        let entries = vec![
+            entry("alice", Difficulty::Easy, 3, 12, true),
+            entry("bob\tthe\nbuilder", Difficulty::Hard, 10, 95, false),
+        ];
+        assert!(load(&path).unwrap().entries.is_empty());
+
+        for entry in &entries {
+            append(&path, entry).unwrap();
+        }
+        let loaded = load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.skipped, 0);
+        assert_eq!(loaded.entries[0], entries[0]);
+        // the tab and newline in the name would have broken the line, so they were saved as spaces
+        assert_eq!(loaded.entries[1].player, "bob the builder");
+        assert_eq!(loaded.entries[1].attempts, 10);
+        assert!(!loaded.entries[1].won);
+    }
+
+    #[test]
+    fn corrupt_and_partial_lines_are_skipped_and_counted() {
+        let path = temp_file("corrupt");
+        let good = entry("alice", Difficulty::Normal, 4, 30, true);
+        let mut text = format!("{}\nnot a score\n\n", good.to_line());
+        text.push_str("bob\tnormal\t1\t100\tmany\twon\t1000\t0\n");
+        text.push_str("carol\tnormal\t1\t100\t5\tdrew\t1000\t0\n");
+        // the game was killed part way through writing this one
+        text.push_str("dave\tnormal\t1\t10");
+        std::fs::write(&path, text).unwrap();
+
+        append(&path, &good).unwrap();
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes.extend_from_slice(b"\xff\xfe\n");
+        std::fs::write(&path, bytes).unwrap();
+        let loaded = load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // the blank line is ignored, and the entry added after the cut-off line is not lost with it
+        assert_eq!(loaded.entries, vec![good.clone(), good]);
+        assert_eq!(loaded.skipped, 5);
+    }
+
+    #[test]
+    fn best_puts_the_fewest_attempts_first_then_the_quickest() {
+        let entries = vec![
+            entry("slow", Difficulty::Normal, 4, 90, true),
+            entry("lost", Difficulty::Normal, 1, 1, false),
+            entry("quick", Difficulty::Normal, 4, 20, true),
+            entry("hard", Difficulty::Hard, 1, 5, true),
+            entry("lucky", Difficulty::Normal, 1, 60, true),
+            entry("steady", Difficulty::Normal, 6, 10, true),
+        ];
+        let names = |limit: usize| -> Vec<String> {
+            best(&entries, Difficulty::Normal, limit).iter().map(|entry| entry.player.clone()).collect()
+        };
+
+        assert_eq!(names(TOP_N), vec!["lucky", "quick", "slow", "steady"]);
+        assert_eq!(names(2), vec!["lucky", "quick"]);
+        assert!(names(0).is_empty());
+        assert!(best(&entries, Difficulty::Easy, TOP_N).is_empty());
+    }
+
+    #[test]
+    fn blank_names_are_anonymous() {
+        assert_eq!(clean_name(" \t\n "), "anonymous");
+        assert_eq!(clean_name("  alice "), "alice");
+    }
+}
@@ -0,0 +1,346 @@
+// local multiplayer over TCP
+//
+// the protocol is plain text, one line per message, so `nc localhost 7878` is a working client:
+//   client -> server:  a number to guess it, `name <NAME>` to pick a name, `quit` (or `q`) to leave
+//   server -> client:  `Too small` / `Too large` / `Correct!` in reply to your own guess,
+//                      and a line of news whenever anyone else joins, leaves or guesses
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::str::FromStr;
+// Arc lets several threads own the same value, Mutex makes sure only one of them changes it at a time
+// an mpsc channel hands values from one thread to another - many senders, one receiver
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::config::Config;
+use crate::game::{Game, Outcome};
+use crate::input::{self, Command};
+use crate::session::Session;
+
+// a client that has not taken a line off its connection for this long is disconnected
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+// the longest line a client may send, newline included - a longer one gets the client disconnected
+pub const MAX_LINE: usize = 1024;
+
+// how the players share the secret number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayMode {
+    // everybody guesses whenever they like - the first to find it wins
+    Race,
+    // players take it in turns, in the order they joined
+    Turns,
+}
+
+impl fmt::Display for PlayMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PlayMode::Race => write!(f, "race"),
+            PlayMode::Turns => write!(f, "turns"),
+        }
+    }
+}
+
+impl FromStr for PlayMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<PlayMode, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "race" => Ok(PlayMode::Race),
+            "turns" => Ok(PlayMode::Turns),
+            _ => Err(format!("unknown mode '{}' (expected race or turns)", s)),
+        }
+    }
+}
+
+struct Player {
+    id: u32,
+    name: String,
+    // lines waiting to go to the client - sending never blocks, the client's writer thread does the writing
+    outbox: Sender<String>,
+}
+
+// everything the client threads share: the current round and who is playing it
+struct Table {
+    session: Session<ChaCha8Rng>,
+    game: Game,
+    mode: PlayMode,
+    players: Vec<Player>,
+    next_id: u32,
+    // index into players of whoever's turn it is (only used in Turns mode)
+    turn: usize,
+}
+
+// open a listener, refusing any address that is reachable from other machines
+// the game is only meant for players on this computer
+pub fn bind(address: &str) -> io::Result<TcpListener> {
+    let addresses: Vec<_> = address.to_socket_addrs()?.collect();
+    if addresses.is_empty() || addresses.iter().any(|a| !a.ip().is_loopback()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not a loopback address (try 127.0.0.1)", address),
+        ));
+    }
+
+    TcpListener::bind(&addresses[..])
+}
+
+// accept players on the listener until the process is stopped
+// each player gets their own thread; the game itself sits behind a Mutex that every thread shares
+pub fn serve(listener: TcpListener, config: Config, mode: PlayMode, seed: Option<u64>) -> io::Result<()> {
+    // ThreadRng cannot be sent between threads, so the server always uses ChaCha8Rng, the same as a seeded game
+    let rng = match seed {
+        Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+        None => ChaCha8Rng::from_entropy(),
+    };
+    let mut session = Session::new(config, rng);
+    let game = session.next_game();
+
+    let table = Arc::new(Mutex::new(Table {
+        session,
+        game,
+        mode,
+        players: Vec::new(),
+        next_id: 1,
+        turn: 0,
+    }));
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            // a client that fails to connect should not bring the whole server down
+            Err(_) => continue,
+        };
+
+        // Arc::clone only copies the pointer - every thread still shares the same Table
+        let table = Arc::clone(&table);
+        thread::spawn(move || handle_client(stream, table));
+    }
+
+    Ok(())
+}
+
+// a poisoned Mutex means another client thread panicked - the table is still usable, so carry on with it
+fn lock(table: &Mutex<Table>) -> MutexGuard<'_, Table> {
+    table.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn handle_client(stream: TcpStream, table: Arc<Mutex<Table>>) {
+    let writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(_) => return,
+    };
+
+    // the table only ever puts lines into the channel, so no socket is written while the lock is held
+    let (outbox, inbox) = mpsc::channel();
+    thread::spawn(move || write_lines(writer, inbox));
+
+    let id = lock(&table).join(outbox);
+
+    // Ok(None) is end of file, which is what we get when the client disconnects
+    let mut reader = BufReader::new(stream);
+    while let Ok(Some(line)) = read_line(&mut reader) {
+        if !lock(&table).handle_line(id, &line) {
+            break;
+        }
+    }
+
+    lock(&table).leave(id);
+}
+
+// read one line from a client, without the newline, or None at end of file
+// take() stops read_until after MAX_LINE bytes, so a client can't make us buffer an endless line
+pub fn read_line<R: BufRead>(reader: &mut R) -> io::Result<Option<String>> {
+    let mut bytes = Vec::new();
+    let read = reader.by_ref().take(MAX_LINE as u64).read_until(b'\n', &mut bytes)?;
+    if read == 0 {
+        return Ok(None);
+    }
+
+    if bytes.ends_with(b"\n") {
+        bytes.pop();
+    } else if read == MAX_LINE {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "the line is too long"));
+    }
+    if bytes.ends_with(b"\r") {
+        bytes.pop();
+    }
+
+    String::from_utf8(bytes)
+        .map(Some)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "the line is not UTF-8"))
+}
+
+// the writing half of a client: everything put in its outbox, until the player leaves and the Sender is dropped
+// a client that stops reading fills up its connection, so a write eventually times out - shutting the connection
+// down then ends the reading loop in handle_client, which takes the player off the table
+fn write_lines(mut stream: TcpStream, inbox: Receiver<String>) {
+    let timeout = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+
+    // iterating over a Receiver waits for each message and stops once every Sender is gone
+    for message in inbox {
+        if timeout.is_err() || writeln!(stream, "{}", message).is_err() {
+            let _ = stream.shutdown(Shutdown::Both);
+            return;
+        }
+    }
+}
+
+// queue one line for a client
+// errors are ignored here: a broken connection is noticed (and cleaned up) by that client's own threads
+fn send(player: &Player, message: &str) {
+    let _ = player.outbox.send(message.to_string());
+}
+
+impl Table {
+    fn join(&mut self, outbox: Sender<String>) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let name = format!("player{}", id);
+        self.broadcast(&format!("{} joined the game", name));
+
+        let player = Player { id, name, outbox };
+        send(&player, &format!("Welcome, {}! Mode: {}.", player.name, self.mode));
+        send(&player, "Type a number to guess, 'name <NAME>' to change your name or 'quit' to leave.");
+        send(&player, &self.round_intro());
+
+        // only the newcomer needs to hear whose turn it is - everybody else already knows
+        if self.mode == PlayMode::Turns {
+            match self.players.get(self.turn) {
+                Some(current) => send(&player, &format!("It's {}'s turn", current.name)),
+                None => send(&player, "Your turn!"),
+            }
+        }
+
+        self.players.push(player);
+        id
+    }
+
+    // deal with one line from a client - returns false when the client wants to leave
+    fn handle_line(&mut self, id: u32, line: &str) -> bool {
+        let index = match self.players.iter().position(|player| player.id == id) {
+            Some(index) => index,
+            None => return false,
+        };
+
+        let text = line.trim();
+        // get(..5) returns None instead of panicking if the line is shorter or the 5th byte splits a character
+        let renaming = text.get(..5).is_some_and(|start| start.eq_ignore_ascii_case("name "));
+        if renaming && !text[5..].trim().is_empty() {
+            let old = self.players[index].name.clone();
+            self.players[index].name = text[5..].trim().to_string();
+            self.broadcast(&format!("{} is now called {}", old, self.players[index].name));
+            return true;
+        }
+
+        let guess = match input::parse_command(text, self.game.low(), self.game.high()) {
+            Ok(Command::Guess(guess)) => guess,
+            Ok(Command::Quit) => return false,
+            Err(e) => {
+                send(&self.players[index], &e.to_string());
+                return true;
+            }
+        };
+
+        if self.mode == PlayMode::Turns && index != self.turn {
+            let waiting_for = self.players[self.turn].name.clone();
+            send(&self.players[index], &format!("Not your turn - waiting for {}", waiting_for));
+            return true;
+        }
+
+        let outcome = self.game.guess(guess);
+        let reply = match outcome {
+            Outcome::TooSmall => "Too small",
+            Outcome::TooLarge => "Too large",
+            Outcome::Correct => "Correct!",
+        };
+        send(&self.players[index], reply);
+
+        let name = self.players[index].name.clone();
+        self.broadcast_except(id, &format!("{} guessed {}: {}", name, guess, outcome));
+
+        if self.game.is_won() {
+            self.broadcast(&format!("{} found the number {} after {} guesses!", name, guess, self.game.attempts()));
+            self.new_round();
+        } else if self.game.is_lost() {
+            self.broadcast(&format!("Out of guesses! The number was {}.", self.game.secret()));
+            self.new_round();
+        } else if self.mode == PlayMode::Turns {
+            self.turn = (self.turn + 1) % self.players.len();
+            self.announce_turn();
+        }
+
+        true
+    }
+
+    fn leave(&mut self, id: u32) {
+        let index = match self.players.iter().position(|player| player.id == id) {
+            Some(index) => index,
+            None => return,
+        };
+
+        let player = self.players.remove(index);
+        self.broadcast(&format!("{} left the game", player.name));
+
+        // keep the turn pointing at the same player - or the next one if it was the leaver's turn
+        let was_their_turn = index == self.turn;
+        if index < self.turn {
+            self.turn -= 1;
+        }
+        if self.turn >= self.players.len() {
+            self.turn = 0;
+        }
+        if was_their_turn {
+            self.announce_turn();
+        }
+    }
+
+    fn new_round(&mut self) {
+        self.game = self.session.next_game();
+        let intro = self.round_intro();
+        self.broadcast(&format!("New round! {}", intro));
+        self.announce_turn();
+    }
+
+    fn round_intro(&self) -> String {
+        match self.game.config().max_attempts {
+            Some(max) => format!(
+                "I'm thinking of a number between {} and {}. You have {} guesses between you.",
+                self.game.low(),
+                self.game.high(),
+                max
+            ),
+            None => format!("I'm thinking of a number between {} and {}.", self.game.low(), self.game.high()),
+        }
+    }
+
+    // in Turns mode, tell everyone whose go it is
+    fn announce_turn(&self) {
+        if self.mode != PlayMode::Turns || self.players.is_empty() {
+            return;
+        }
+
+        let turn = self.turn;
+        let name = self.players[turn].name.clone();
+        send(&self.players[turn], "Your turn!");
+        self.broadcast_except(self.players[turn].id, &format!("It's {}'s turn", name));
+    }
+
+    fn broadcast(&self, message: &str) {
+        for player in &self.players {
+            send(player, message);
+        }
+    }
+
+    fn broadcast_except(&self, id: u32, message: &str) {
+        for player in self.players.iter().filter(|player| player.id != id) {
+            send(player, message);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+// RngCore is the object-safe part of Rng - it lets us store "any random number generator" in a Box
+// SeedableRng is the trait for generators that can be rebuilt from a seed
+use rand::{RngCore, SeedableRng};
+// StdRng is allowed to change its algorithm between rand releases, which would quietly change every seeded game
+// ChaCha8Rng is a named algorithm, so a seed gives the same numbers whichever version built the game
+use rand_chacha::ChaCha8Rng;
+
+use crate::config::Config;
+use crate::game::Game;
+use crate::variant::{GameVariant, VariantKind};
+
+// pick the random source for a run of the game
+// with a seed the same secret numbers come out every time, which makes bugs reproducible
+// without one we fall back to the thread-local generator the game has always used
+pub fn rng_from_seed(seed: Option<u64>) -> Box<dyn RngCore> {
+    match seed {
+        Some(seed) => Box::new(ChaCha8Rng::seed_from_u64(seed)),
+        None => Box::new(rand::thread_rng()),
+    }
+}
+
+// a Session deals out one Game after another from the same random source
+// the generic R means tests can hand in any generator they like
+pub struct Session<R> {
+    config: Config,
+    rng: R,
+    rounds: u32,
+}
+
+impl<R: RngCore> Session<R> {
+    pub fn new(config: Config, rng: R) -> Session<R> {
+        Session { config, rng, rounds: 0 }
+    }
+
+    // start the next round - the secret number is drawn from this session's generator
+    pub fn next_game(&mut self) -> Game {
+        self.rounds += 1;
+        Game::random(self.config, &mut self.rng)
+    }
+
+    // start the next round with a different set of rules - the classic variant draws the same secret as next_game
+    pub fn next_variant(&mut self, kind: VariantKind) -> Box<dyn GameVariant> {
+        self.rounds += 1;
+        kind.setup(self.config, &mut self.rng)
+    }
+
+    // how many rounds have been started so far
+    pub fn rounds(&self) -> u32 {
+        self.rounds
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    // the generator behind this session, for anything else that needs randomness (like a computer player)
+    // sharing it means a seeded session stays repeatable from start to finish
+    pub fn rng(&mut self) -> &mut R {
+        &mut self.rng
+    }
+}
+
+impl Session<ChaCha8Rng> {
+    // a session whose secret numbers are fully decided by the seed
+    pub fn seeded(config: Config, seed: u64) -> Session<ChaCha8Rng> {
+        Session::new(config, ChaCha8Rng::seed_from_u64(seed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secrets(session: &mut Session<impl RngCore>) -> Vec<u32> {
+        (0..8).map(|_| session.next_game().secret()).collect()
+    }
+
+    // these numbers are part of the promise a seed makes - if this fails, old seeds have stopped replaying
+    #[test]
+    fn a_seed_always_gives_the_same_secrets() {
+        let config = Config::new(1, 100, None).unwrap();
+        let expected = vec![23, 69, 15, 96, 78, 43, 35, 63];
+
+        assert_eq!(secrets(&mut Session::new(config, rng_from_seed(Some(42)))), expected);
+        assert_eq!(secrets(&mut Session::seeded(config, 42)), expected);
+        assert_ne!(secrets(&mut Session::seeded(config, 43)), expected);
+    }
+}
@@ -0,0 +1,256 @@
+// a computer player for the number game
+// it only ever sees the same Too small / Too large feedback a human gets
+use std::fmt;
+use std::str::FromStr;
+
+use rand::distributions::Uniform;
+use rand::{Rng, RngCore};
+
+use crate::game::{Game, Outcome};
+
+// a way of picking the next guess
+// low and high are the bounds the player has worked out so far (inclusive), so the answer must be in low..=high
+// the random number generator is passed in so that seeded runs stay repeatable
+pub trait Strategy {
+    fn next_guess(&mut self, low: u32, high: u32, rng: &mut dyn RngCore) -> u32;
+}
+
+// always guess the middle of what is left - never needs more than log2(range) + 1 guesses
+pub struct BinarySearch;
+
+impl Strategy for BinarySearch {
+    fn next_guess(&mut self, low: u32, high: u32, _rng: &mut dyn RngCore) -> u32 {
+        // low + (high - low) / 2 is the same as (low + high) / 2 but cannot overflow
+        low + (high - low) / 2
+    }
+}
+
+// the most guesses binary search can need for the range low..=high
+// each guess halves what is left, so it is the number of bits in the count of possible numbers
+pub fn worst_case(low: u32, high: u32) -> u32 {
+    let count = u64::from(high - low) + 1;
+    64 - count.leading_zeros()
+}
+
+// guess any number that is still possible
+pub struct RandomGuess;
+
+impl Strategy for RandomGuess {
+    fn next_guess(&mut self, low: u32, high: u32, rng: &mut dyn RngCore) -> u32 {
+        rng.sample(Uniform::new_inclusive(low, high))
+    }
+}
+
+// count up from the bottom of the range one number at a time
+pub struct Linear;
+
+impl Strategy for Linear {
+    fn next_guess(&mut self, low: u32, _high: u32, _rng: &mut dyn RngCore) -> u32 {
+        low
+    }
+}
+
+// the built-in strategies, so they can be chosen by name on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrategyKind {
+    BinarySearch,
+    Random,
+    Linear,
+}
+
+impl StrategyKind {
+    pub const ALL: [StrategyKind; 3] = [StrategyKind::BinarySearch, StrategyKind::Random, StrategyKind::Linear];
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            StrategyKind::BinarySearch => "binary",
+            StrategyKind::Random => "random",
+            StrategyKind::Linear => "linear",
+        }
+    }
+
+    // Box<dyn Strategy> lets us hold any of the strategy structs behind the same type
+    pub fn build(&self) -> Box<dyn Strategy> {
+        match *self {
+            StrategyKind::BinarySearch => Box::new(BinarySearch),
+            StrategyKind::Random => Box::new(RandomGuess),
+            StrategyKind::Linear => Box::new(Linear),
+        }
+    }
+}
+
+impl fmt::Display for StrategyKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for StrategyKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<StrategyKind, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "binary" | "bisect" => Ok(StrategyKind::BinarySearch),
+            "random" => Ok(StrategyKind::Random),
+            "linear" => Ok(StrategyKind::Linear),
+            _ => Err(format!("unknown strategy '{}' (expected binary, random or linear)", s)),
+        }
+    }
+}
+
+// the feedback from the game contradicted itself
+// if this ever happens the comparison logic in Game::guess is broken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveError {
+    // after this guess there was no number left that fits every answer so far
+    NoCandidates { guess: u32, outcome: Outcome },
+    // the game's answer does not match the secret number it revealed afterwards
+    WrongFeedback { guess: u32, outcome: Outcome, secret: u32 },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SolveError::NoCandidates { guess, outcome } => {
+                write!(f, "answer '{}' to guess {} rules out every remaining number", outcome, guess)
+            }
+            SolveError::WrongFeedback { guess, outcome, secret } => {
+                write!(f, "guess {} was answered '{}' but the secret was {}", guess, outcome, secret)
+            }
+        }
+    }
+}
+
+// play a game to the end with the given strategy
+// returns whether the game was won; the guesses themselves are in game.history()
+pub fn solve(game: &mut Game, strategy: &mut dyn Strategy, rng: &mut dyn RngCore) -> Result<bool, SolveError> {
+    let mut low = game.low();
+    let mut high = game.high();
+
+    while !game.is_over() {
+        let guess = strategy.next_guess(low, high, rng);
+        let outcome = game.guess(guess);
+
+        // narrow the bounds using the answer, checking that the answer still leaves somewhere to go
+        match outcome {
+            Outcome::Correct => break,
+            Outcome::TooSmall if guess < high => low = low.max(guess + 1),
+            Outcome::TooLarge if guess > low => high = high.min(guess - 1),
+            _ => return Err(SolveError::NoCandidates { guess, outcome }),
+        }
+
+        if low > high {
+            return Err(SolveError::NoCandidates { guess, outcome });
+        }
+    }
+
+    check_feedback(game)?;
+    Ok(game.is_won())
+}
+
+// replay the whole history against the secret number and make sure every answer was right
+pub fn check_feedback(game: &Game) -> Result<(), SolveError> {
+    let secret = game.secret();
+
+    for attempt in game.history() {
+        let expected = if attempt.guess < secret {
+            Outcome::TooSmall
+        } else if attempt.guess > secret {
+            Outcome::TooLarge
+        } else {
+            Outcome::Correct
+        };
+
+        if attempt.outcome != expected {
+            return Err(SolveError::WrongFeedback {
+                guess: attempt.guess,
+                outcome: attempt.outcome,
+                secret,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::session;
+
+    // play a game for every secret in low..=high, with no limit on the guesses
+    fn solve_every_secret(kind: StrategyKind, low: u32, high: u32) -> Vec<Game> {
+        let config = Config::new(low, high, None).unwrap();
+        let mut rng = session::rng_from_seed(Some(7));
+
+        (low..=high)
+            .map(|secret| {
+                let mut game = Game::new(secret, config);
+                assert_eq!(solve(&mut game, kind.build().as_mut(), &mut rng), Ok(true), "secret {}", secret);
+                game
+            })
+            .collect()
+    }
+
+    #[test]
+    fn binary_search_feedback_is_consistent_and_within_the_worst_case() {
+        for &(low, high) in [(1, 2), (1, 100), (7, 263)].iter() {
+            let limit = worst_case(low, high);
+            for game in solve_every_secret(StrategyKind::BinarySearch, low, high) {
+                assert_eq!(check_feedback(&game), Ok(()));
+                let (secret, attempts) = (game.secret(), game.attempts());
+                assert!(attempts <= limit, "{} took {} guesses in {}..={}", secret, attempts, low, high);
+            }
+        }
+    }
+
+    #[test]
+    fn the_worst_case_is_reached_but_never_beaten() {
+        let games = solve_every_secret(StrategyKind::BinarySearch, 1, 100);
+        let most = games.iter().map(|game| game.attempts()).max();
+        assert_eq!(most, Some(worst_case(1, 100)));
+    }
+
+    #[test]
+    fn worst_case_at_the_edges() {
+        // Config refuses a one-number range, but the formula still has to get it right
+        assert_eq!(worst_case(5, 5), 1);
+        assert_eq!(worst_case(1, 2), 2);
+        // a power of two needs one more guess than the power before it
+        assert_eq!(worst_case(1, 7), 3);
+        assert_eq!(worst_case(1, 8), 4);
+        assert_eq!(worst_case(1, 1024), 11);
+        assert_eq!(worst_case(1, 1000), 10);
+        // the whole u32 range has 2^32 numbers, which only fits because the count is a u64
+        assert_eq!(worst_case(0, u32::MAX), 33);
+        assert_eq!(worst_case(1, u32::MAX), 32);
+    }
+
+    #[test]
+    fn random_and_linear_always_finish() {
+        for kind in [StrategyKind::Random, StrategyKind::Linear] {
+            for game in solve_every_secret(kind, 1, 50) {
+                assert_eq!(check_feedback(&game), Ok(()));
+                // every wrong guess rules out at least one number
+                assert!(game.attempts() <= 50);
+            }
+        }
+    }
+
+    #[test]
+    fn linear_counts_up_from_the_bottom() {
+        let games = solve_every_secret(StrategyKind::Linear, 10, 20);
+        let attempts: Vec<u32> = games.iter().map(|game| game.attempts()).collect();
+        assert_eq!(attempts, (1..=11).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn a_limited_game_can_be_lost() {
+        let mut game = Game::new(1, Config::new(1, 100, Some(3)).unwrap());
+        let mut rng = session::rng_from_seed(Some(1));
+
+        assert_eq!(solve(&mut game, &mut BinarySearch, &mut rng), Ok(false));
+        assert_eq!(game.attempts(), 3);
+    }
+}
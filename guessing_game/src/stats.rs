@@ -0,0 +1,202 @@
+// statistics over every round in the score file: how often the player wins, how many guesses it takes,
+// and how long their winning streaks are
+use std::collections::BTreeMap;
+
+use crate::config::Difficulty;
+use crate::scores::ScoreEntry;
+
+// which rounds to include - None in any field means no restriction
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filter {
+    pub difficulty: Option<Difficulty>,
+    // only rounds from this player (compared ignoring case)
+    pub player: Option<String>,
+    // seconds since the unix epoch - `from` is included, `until` is not
+    pub from: Option<u64>,
+    pub until: Option<u64>,
+}
+
+impl Filter {
+    pub fn matches(&self, entry: &ScoreEntry) -> bool {
+        self.difficulty.is_none_or(|difficulty| entry.difficulty == difficulty)
+            && self.player.as_ref().is_none_or(|player| entry.player.eq_ignore_ascii_case(player))
+            && self.from.is_none_or(|from| entry.finished_at >= from)
+            && self.until.is_none_or(|until| entry.finished_at < until)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    pub played: usize,
+    pub won: usize,
+    // the attempt figures only count won rounds - a lost round always used up every guess
+    // None when nothing has been won yet
+    pub mean_attempts: Option<f64>,
+    pub median_attempts: Option<f64>,
+    // wins in a row at the end of the history, and the most wins in a row ever
+    pub current_streak: usize,
+    pub longest_streak: usize,
+    // number of guesses -> how many won rounds needed exactly that many
+    pub histogram: BTreeMap<u32, usize>,
+}
+
+impl Stats {
+    // the share of rounds that were won, from 0.0 to 1.0 - None when no rounds were played
+    pub fn win_rate(&self) -> Option<f64> {
+        if self.played == 0 {
+            None
+        } else {
+            Some(self.won as f64 / self.played as f64)
+        }
+    }
+
+    pub fn lost(&self) -> usize {
+        self.played - self.won
+    }
+}
+
+// work out the statistics for the rounds that pass the filter
+pub fn compute(entries: &[ScoreEntry], filter: &Filter) -> Stats {
+    let mut rounds: Vec<&ScoreEntry> = entries.iter().filter(|entry| filter.matches(entry)).collect();
+    // streaks only make sense in the order the rounds were played
+    // sort_by_key is stable, so rounds finished in the same second keep their order in the file
+    rounds.sort_by_key(|entry| entry.finished_at);
+
+    let mut stats = Stats {
+        played: rounds.len(),
+        ..Stats::default()
+    };
+
+    let mut attempts: Vec<u32> = Vec::new();
+    let mut streak = 0;
+    for entry in rounds {
+        if entry.won {
+            stats.won += 1;
+            attempts.push(entry.attempts);
+            *stats.histogram.entry(entry.attempts).or_insert(0) += 1;
+            streak += 1;
+            stats.longest_streak = stats.longest_streak.max(streak);
+        } else {
+            streak = 0;
+        }
+    }
+    stats.current_streak = streak;
+
+    if !attempts.is_empty() {
+        let total: u64 = attempts.iter().map(|&a| u64::from(a)).sum();
+        stats.mean_attempts = Some(total as f64 / attempts.len() as f64);
+        stats.median_attempts = Some(median(&mut attempts));
+    }
+
+    stats
+}
+
+// the middle value once sorted, or halfway between the two middle values for an even count
+fn median(values: &mut [u32]) -> f64 {
+    values.sort_unstable();
+    let middle = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (f64::from(values[middle - 1]) + f64::from(values[middle])) / 2.0
+    } else {
+        f64::from(values[middle])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    use crate::scores;
+
+    fn round(player: &str, attempts: u32, won: bool, finished_at: u64) -> ScoreEntry {
+        ScoreEntry {
+            player: player.to_string(),
+            difficulty: Difficulty::Normal,
+            low: 1,
+            high: 100,
+            attempts,
+            won,
+            duration: Duration::from_secs(30),
+            finished_at,
+        }
+    }
+
+    #[test]
+    fn win_rate_mean_median_and_streaks() {
+        // in the file out of order - the streaks follow finished_at
+        let entries = vec![
+            round("alice", 3, true, 30),
+            round("alice", 7, true, 10),
+            round("alice", 10, false, 40),
+            round("alice", 4, true, 20),
+            round("alice", 4, true, 50),
+        ];
+        let stats = compute(&entries, &Filter::default());
+
+        assert_eq!((stats.played, stats.won, stats.lost()), (5, 4, 1));
+        assert_eq!(stats.win_rate(), Some(0.8));
+        // the lost round's 10 guesses are left out of the averages
+        assert_eq!(stats.mean_attempts, Some(4.5));
+        assert_eq!(stats.median_attempts, Some(4.0));
+        assert_eq!((stats.current_streak, stats.longest_streak), (1, 3));
+        assert_eq!(stats.histogram.into_iter().collect::<Vec<_>>(), vec![(3, 1), (4, 2), (7, 1)]);
+    }
+
+    #[test]
+    fn no_rounds_or_no_wins() {
+        let stats = compute(&[], &Filter::default());
+        assert_eq!(stats.win_rate(), None);
+        assert_eq!(stats.mean_attempts, None);
+
+        let stats = compute(&[round("bob", 10, false, 0)], &Filter::default());
+        assert_eq!(stats.win_rate(), Some(0.0));
+        assert_eq!((stats.mean_attempts, stats.median_attempts), (None, None));
+    }
+
+    #[test]
+    fn huge_attempt_counts_do_not_overflow_the_mean() {
+        let entries = vec![round("alice", u32::MAX, true, 0), round("alice", u32::MAX, true, 1)];
+        let stats = compute(&entries, &Filter::default());
+        assert_eq!(stats.mean_attempts, Some(f64::from(u32::MAX)));
+        assert_eq!(stats.median_attempts, Some(f64::from(u32::MAX)));
+    }
+
+    #[test]
+    fn from_includes_its_day_and_until_stops_before_its_own() {
+        let day = 86_400;
+        let start = scores::parse_date("2024-03-01").unwrap();
+        let entries = vec![
+            round("alice", 1, true, start - 1),
+            round("alice", 2, true, start),
+            round("Alice", 3, true, start + day - 1),
+            round("bob", 4, true, start + day - 1),
+            round("alice", 5, true, start + day),
+        ];
+
+        // what `stats --name ALICE --from 2024-03-01 --to 2024-03-01` asks for
+        let filter = Filter {
+            difficulty: None,
+            player: Some(String::from("ALICE")),
+            from: Some(start),
+            until: Some(start + day),
+        };
+        let stats = compute(&entries, &filter);
+        assert_eq!(stats.histogram.keys().cloned().collect::<Vec<_>>(), vec![2, 3]);
+
+        let hard = Filter { difficulty: Some(Difficulty::Hard), ..Filter::default() };
+        assert_eq!(compute(&entries, &hard).played, 0);
+    }
+
+    #[test]
+    fn the_last_possible_date_still_filters() {
+        let last = scores::parse_date("9999-12-31").unwrap();
+        let filter = Filter { from: Some(last), until: last.checked_add(86_400), ..Filter::default() };
+
+        let entries = vec![round("alice", 1, true, last), round("alice", 2, true, u64::MAX)];
+        assert_eq!(compute(&entries, &filter).played, 1);
+        // with no end date even the largest timestamp is included
+        let open = Filter { from: Some(last), ..Filter::default() };
+        assert_eq!(compute(&entries, &open).played, 2);
+    }
+}
@@ -0,0 +1,319 @@
+// session transcripts: a record of every prompt, guess and outcome, which can be replayed later
+//
+// the file is plain text with one event per line, fields separated by tabs:
+//   <millis since 1970>  round   <low> <high> <max attempts or -> <secret>
+//   <millis since 1970>  prompt  <text shown to the player>
+//   <millis since 1970>  input   <line typed by the player>
+//   <millis since 1970>  guess   <number> <outcome>
+//   <millis since 1970>  end     <won | lost | quit | eof>
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::game::{Game, Outcome};
+
+const HEADER: &str = "# guessing_game transcript v1";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    // a new round started - the secret is stored so that the round can be replayed exactly
+    Round { config: Config, secret: u32 },
+    Prompt(String),
+    Input(String),
+    Guess { guess: u32, outcome: Outcome },
+    End(String),
+}
+
+// one line of a transcript, with where it came from so that problems can be pointed at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub line: usize,
+    pub at_millis: u64,
+    pub event: Event,
+}
+
+// writes events to any Write - a file normally, but a Vec<u8> works just as well
+pub struct Recorder<W: Write> {
+    out: W,
+}
+
+impl<W: Write> Recorder<W> {
+    pub fn new(mut out: W) -> io::Result<Recorder<W>> {
+        writeln!(out, "{}", HEADER)?;
+        Ok(Recorder { out })
+    }
+
+    pub fn record(&mut self, event: &Event) -> io::Result<()> {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+
+        let fields = match *event {
+            Event::Round { ref config, secret } => {
+                let max = config.max_attempts.map_or(String::from("-"), |max| max.to_string());
+                format!("round\t{}\t{}\t{}\t{}", config.low, config.high, max, secret)
+            }
+            Event::Prompt(ref text) => format!("prompt\t{}", escape(text)),
+            Event::Input(ref text) => format!("input\t{}", escape(text)),
+            Event::Guess { guess, outcome } => format!("guess\t{}\t{}", guess, outcome),
+            Event::End(ref how) => format!("end\t{}", escape(how)),
+        };
+
+        writeln!(self.out, "{}\t{}", millis, fields)?;
+        // flush after every event so that the transcript is complete even if the game is killed
+        self.out.flush()
+    }
+}
+
+// the player's input could contain tabs or backslashes, which would confuse the file format
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n").replace('\r', "\\r")
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::new();
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('t') => result.push('\t'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+
+    result
+}
+
+fn parse_outcome(text: &str) -> Option<Outcome> {
+    match text {
+        "too small" => Some(Outcome::TooSmall),
+        "correct" => Some(Outcome::Correct),
+        "too large" => Some(Outcome::TooLarge),
+        _ => None,
+    }
+}
+
+// a transcript that could not be read, or a replay that did not match
+#[derive(Debug)]
+pub enum TranscriptError {
+    Io(io::Error),
+    Malformed { line: usize, reason: String },
+    // a guess was recorded before any round started
+    NoRound { line: usize },
+    Mismatch { line: usize, guess: u32, recorded: Outcome, replayed: Outcome },
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TranscriptError::Io(ref e) => write!(f, "{}", e),
+            TranscriptError::Malformed { line, ref reason } => write!(f, "line {}: {}", line, reason),
+            TranscriptError::NoRound { line } => write!(f, "line {}: guess recorded outside of a round", line),
+            TranscriptError::Mismatch { line, guess, recorded, replayed } => write!(
+                f,
+                "line {}: guess {} was recorded as '{}' but now comes out '{}'",
+                line, guess, recorded, replayed
+            ),
+        }
+    }
+}
+
+impl From<io::Error> for TranscriptError {
+    fn from(e: io::Error) -> TranscriptError {
+        TranscriptError::Io(e)
+    }
+}
+
+// read a whole transcript back into a list of entries
+pub fn read<R: BufRead>(reader: R) -> Result<Vec<Entry>, TranscriptError> {
+    let mut entries = Vec::new();
+
+    // enumerate() counts from 0, so add 1 to get line numbers the way an editor shows them
+    for (index, line) in reader.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line?;
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let malformed = |reason: &str| TranscriptError::Malformed {
+            line: line_number,
+            reason: reason.to_string(),
+        };
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() < 2 {
+            return Err(malformed("expected a timestamp and an event"));
+        }
+
+        let at_millis = fields[0].parse().map_err(|_| malformed("bad timestamp"))?;
+        let number = |i: usize| -> Result<u32, TranscriptError> {
+            fields.get(i).and_then(|field| field.parse().ok()).ok_or_else(|| malformed("bad number"))
+        };
+
+        let event = match (fields[1], fields.len()) {
+            ("round", 6) => {
+                let max_attempts = match fields[4] {
+                    "-" => None,
+                    _ => Some(number(4)?),
+                };
+                let config = Config::new(number(2)?, number(3)?, max_attempts)
+                    .map_err(|e| malformed(&e.to_string()))?;
+                Event::Round { config, secret: number(5)? }
+            }
+            ("prompt", 3) => Event::Prompt(unescape(fields[2])),
+            ("input", 3) => Event::Input(unescape(fields[2])),
+            ("guess", 4) => Event::Guess {
+                guess: number(2)?,
+                outcome: parse_outcome(fields[3]).ok_or_else(|| malformed("bad outcome"))?,
+            },
+            ("end", 3) => Event::End(unescape(fields[2])),
+            _ => return Err(malformed("unknown event")),
+        };
+
+        entries.push(Entry {
+            line: line_number,
+            at_millis,
+            event,
+        });
+    }
+
+    Ok(entries)
+}
+
+// what a successful replay went through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ReplaySummary {
+    pub rounds: u32,
+    pub guesses: u32,
+}
+
+// play every recorded guess against a fresh Game and check that the outcomes have not changed
+pub fn replay(entries: &[Entry]) -> Result<ReplaySummary, TranscriptError> {
+    let mut summary = ReplaySummary::default();
+    let mut game: Option<Game> = None;
+
+    for entry in entries {
+        match entry.event {
+            Event::Round { config, secret } => {
+                game = Some(Game::new(secret, config));
+                summary.rounds += 1;
+            }
+            Event::Guess { guess, outcome } => {
+                let game = game.as_mut().ok_or(TranscriptError::NoRound { line: entry.line })?;
+                let replayed = game.guess(guess);
+
+                if replayed != outcome {
+                    return Err(TranscriptError::Mismatch {
+                        line: entry.line,
+                        guess,
+                        recorded: outcome,
+                        replayed,
+                    });
+                }
+                summary.guesses += 1;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events() -> Vec<Event> {
+        vec![
+            Event::Round { config: Config::new(1, 100, Some(7)).unwrap(), secret: 42 },
+            Event::Prompt(String::from("Please input your guess.")),
+            // input with the characters the file format has to escape
+            Event::Input(String::from("\t50\\n\r\n")),
+            Event::Guess { guess: 50, outcome: Outcome::TooLarge },
+            Event::Guess { guess: 25, outcome: Outcome::TooSmall },
+            Event::Guess { guess: 42, outcome: Outcome::Correct },
+            Event::End(String::from("won")),
+            Event::Round { config: Config::new(0, u32::MAX, None).unwrap(), secret: u32::MAX },
+            Event::End(String::from("eof")),
+        ]
+    }
+
+    fn record(events: &[Event]) -> Vec<u8> {
+        let mut recorder = Recorder::new(Vec::new()).unwrap();
+        for event in events {
+            recorder.record(event).unwrap();
+        }
+        recorder.out
+    }
+
+    fn malformed_line(text: &str) -> Option<usize> {
+        match read(text.as_bytes()) {
+            Err(TranscriptError::Malformed { line, .. }) => Some(line),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn a_recording_reads_back_as_the_same_events() {
+        let bytes = record(&events());
+        assert!(bytes.starts_with(HEADER.as_bytes()));
+
+        let entries = read(&bytes[..]).unwrap();
+        let read_back: Vec<Event> = entries.iter().map(|entry| entry.event.clone()).collect();
+        assert_eq!(read_back, events());
+        // the header is line 1, so the events start on line 2
+        assert_eq!(entries[0].line, 2);
+
+        assert_eq!(replay(&entries).unwrap(), ReplaySummary { rounds: 2, guesses: 3 });
+    }
+
+    #[test]
+    fn truncated_or_corrupt_transcripts_are_errors() {
+        let bytes = record(&events());
+        let text = String::from_utf8(bytes).unwrap();
+
+        // a file cut off at any byte has to read as entries or as an error, never as a panic
+        for cut in 0..text.len() {
+            let _ = read(&text.as_bytes()[..cut]);
+        }
+        assert_eq!(malformed_line(&text[..text.len() - "\tend\teof\n".len()]), Some(10));
+
+        assert_eq!(malformed_line("12"), Some(1));
+        assert_eq!(malformed_line("soon\tend\twon"), Some(1));
+        assert_eq!(malformed_line("99999999999999999999999\tend\twon"), Some(1));
+        assert_eq!(malformed_line("1\tguess\t50"), Some(1));
+        assert_eq!(malformed_line("1\tguess\tfifty\tcorrect"), Some(1));
+        assert_eq!(malformed_line("1\tguess\t50\tclose"), Some(1));
+        assert_eq!(malformed_line("1\tround\t9\t1\t-\t5"), Some(1));
+        assert_eq!(malformed_line("# comment\n\n1\tdance\tnow"), Some(3));
+
+        // bytes that are not UTF-8 come back as an I/O error
+        assert!(matches!(read(&b"1\tinput\t\xff\n"[..]), Err(TranscriptError::Io(_))));
+    }
+
+    #[test]
+    fn replay_catches_changed_outcomes_and_guesses_outside_a_round() {
+        let mut text = String::from_utf8(record(&events())).unwrap();
+        text = text.replace("guess\t25\ttoo small", "guess\t25\ttoo large");
+        let entries = read(text.as_bytes()).unwrap();
+        assert!(matches!(
+            replay(&entries),
+            Err(TranscriptError::Mismatch { guess: 25, recorded: Outcome::TooLarge, replayed: Outcome::TooSmall, .. })
+        ));
+
+        let entries = read("1\tguess\t5\tcorrect".as_bytes()).unwrap();
+        assert!(matches!(replay(&entries), Err(TranscriptError::NoRound { line: 1 })));
+    }
+}
@@ -0,0 +1,254 @@
+// a full-screen terminal front-end for the number game and its variants
+// it is drawn with plain ANSI escape codes, so it needs no extra crates - just a terminal that understands them
+use std::io::{self, BufRead, Write};
+
+use crate::game::Game;
+use crate::hint::{self, Thresholds};
+use crate::input;
+use crate::messages::{Catalog, Key};
+use crate::variant::{GameVariant, Prompt};
+
+// switch to the terminal's alternate screen (so the old contents come back afterwards) and back again
+const ENTER_SCREEN: &str = "\x1b[?1049h";
+const LEAVE_SCREEN: &str = "\x1b[?1049l";
+// clear the screen and move the cursor to the top left corner
+const CLEAR: &str = "\x1b[2J\x1b[H";
+
+const BAR_WIDTH: u32 = 50;
+// only the most recent guesses fit on the screen
+const HISTORY_ROWS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TuiEnd {
+    Finished,
+    Quit,
+    Eof,
+}
+
+// the range bar: '#' for numbers that are still possible, '-' for the ones that have been ruled out
+pub fn range_bar(game: &Game) -> String {
+    let (low, high) = game.narrowed_range();
+    // u64 so that a range of nearly 4 billion numbers times the bar width cannot overflow
+    let span = u64::from(game.high() - game.low()) + 1;
+
+    (0..BAR_WIDTH)
+        .map(|cell| {
+            // the first and last number covered by this cell of the bar
+            let first = u64::from(game.low()) + u64::from(cell) * span / u64::from(BAR_WIDTH);
+            let last = u64::from(game.low()) + (u64::from(cell) + 1) * span / u64::from(BAR_WIDTH);
+            let last = last.max(first + 1) - 1;
+
+            if last >= u64::from(low) && first <= u64::from(high) {
+                '#'
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+// build the whole screen as one string - keeping this separate from the drawing makes it easy to check
+// the range bar and the history need the single secret behind the round, other variants show their rules instead
+pub fn render(variant: &dyn GameVariant, messages: &Catalog, message: &str) -> String {
+    let mut screen = String::new();
+
+    screen.push_str(&format!("  {}\n\n", messages.text(Key::TuiTitle, &[])));
+    match variant.game() {
+        Some(game) => {
+            let (low, high) = game.narrowed_range();
+            screen.push_str(&format!("  {} [{}] {}\n", game.low(), range_bar(game), game.high()));
+            screen.push_str(&format!("  {}\n\n", messages.text(Key::TuiStillPossible, &[&low, &high])));
+        }
+        None => {
+            for line in messages.rules(&variant.rules()).lines() {
+                screen.push_str(&format!("  {}\n", line));
+            }
+            screen.push('\n');
+        }
+    }
+
+    match variant.attempts_left() {
+        Some(left) => screen.push_str(&format!("  {}\n\n", messages.text(Key::TuiGuessesLeft, &[&left]))),
+        None => screen.push_str(&format!("  {}\n\n", messages.text(Key::TuiGuessesSoFar, &[&variant.attempts()]))),
+    }
+
+    if let Some(game) = variant.game() {
+        screen.push_str(&format!("  {}\n", messages.text(Key::TuiHistory, &[])));
+        let history = game.history();
+        // skip everything except the last HISTORY_ROWS guesses
+        let skip = history.len().saturating_sub(HISTORY_ROWS);
+        for (index, attempt) in history.iter().enumerate().skip(skip) {
+            let outcome = messages.outcome_word(attempt.outcome);
+            screen.push_str(&format!("  {:>3}. {:>10}  {}\n", index + 1, attempt.guess, outcome));
+        }
+        if history.is_empty() {
+            screen.push_str(&format!("       {}\n", messages.text(Key::TuiNoGuesses, &[])));
+        }
+    }
+
+    screen.push_str(&format!("\n  {}\n\n", message));
+    screen
+}
+
+// play one round full-screen, reading lines from input and drawing to out
+pub fn play_round<R: BufRead, W: Write>(
+    variant: &mut dyn GameVariant,
+    hints: Option<&Thresholds>,
+    messages: &Catalog,
+    mut input: R,
+    mut out: W,
+) -> io::Result<TuiEnd> {
+    write!(out, "{}", ENTER_SCREEN)?;
+    let result = round_loop(variant, hints, messages, &mut input, &mut out);
+    // always give the terminal its normal screen back, even if drawing failed part way through
+    write!(out, "{}", LEAVE_SCREEN)?;
+    out.flush()?;
+    result
+}
+
+fn round_loop<R: BufRead, W: Write>(
+    variant: &mut dyn GameVariant,
+    hints: Option<&Thresholds>,
+    messages: &Catalog,
+    input: &mut R,
+    out: &mut W,
+) -> io::Result<TuiEnd> {
+    let mut message = match variant.prompt() {
+        Prompt::Guess => messages.text(Key::TuiTypeNumber, &[]),
+        Prompt::Judge(_) => messages.text(Key::TuiTypeAnswer, &[]),
+    };
+
+    loop {
+        let prompt = match variant.prompt() {
+            Prompt::Guess => messages.text(Key::TuiGuessPrompt, &[]),
+            Prompt::Judge(guess) => messages.text(Key::TuiJudgePrompt, &[&guess]),
+        };
+        write!(out, "{}{}  {}", CLEAR, render(variant, messages, &message), prompt)?;
+        out.flush()?;
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(TuiEnd::Eof);
+        }
+
+        if input::is_quit(&line) {
+            return Ok(TuiEnd::Quit);
+        }
+
+        message = match variant.play(&line) {
+            Ok(feedback) => format!("{}: {}", line.trim(), messages.feedback(&feedback)),
+            Err(e) => {
+                message = messages.input_error(&e);
+                continue;
+            }
+        };
+
+        if let (Some(thresholds), Some(game)) = (hints, variant.game()) {
+            if let Some(hint) = hint::for_last_guess(game, thresholds) {
+                message = format!("{} {}", message, messages.hint(&hint));
+            }
+        }
+
+        if variant.is_over() {
+            return Ok(TuiEnd::Finished);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::messages::Locale;
+    use crate::session;
+    use crate::variant::Classic;
+
+    // play a classic round of 1 to 100 with the given lines, returning everything drawn
+    // WRONG in the lines stands for a guess that is sure to miss
+    fn play(lines: &str, hints: Option<&Thresholds>) -> String {
+        let mut variant = Classic::setup(Config::new(1, 100, None).unwrap(), &mut session::rng_from_seed(Some(1)));
+        let wrong = if variant.game().unwrap().secret() == 1 { 100 } else { 1 };
+        let input = lines.replace("WRONG", &wrong.to_string());
+
+        let mut out = Vec::new();
+        let messages = Catalog::new(Locale::English);
+        let end = play_round(&mut variant, hints, &messages, input.as_bytes(), &mut out).unwrap();
+        assert_eq!(end, TuiEnd::Quit);
+        String::from_utf8(out).unwrap()
+    }
+
+    fn game(low: u32, high: u32, secret: u32, guesses: &[u32]) -> Game {
+        let mut game = Game::new(secret, Config::new(low, high, None).unwrap());
+        for &guess in guesses {
+            game.guess(guess);
+        }
+        game
+    }
+
+    // "#" repeated, then "-" repeated, then "#" again ...
+    fn runs(parts: &[(char, usize)]) -> String {
+        parts.iter().map(|&(c, count)| c.to_string().repeat(count)).collect()
+    }
+
+    #[test]
+    fn the_bar_narrows_with_the_range() {
+        assert_eq!(range_bar(&game(1, 100, 42, &[])), runs(&[('#', 50)]));
+        // every cell covers two numbers, so 1 to 49 is the first 25 cells
+        assert_eq!(range_bar(&game(1, 100, 42, &[50])), runs(&[('#', 25), ('-', 25)]));
+        assert_eq!(range_bar(&game(1, 100, 42, &[50, 25])), runs(&[('-', 12), ('#', 13), ('-', 25)]));
+    }
+
+    #[test]
+    fn a_one_number_range_still_shows_its_cell() {
+        // 42 is the second number of cell 20
+        assert_eq!(range_bar(&game(1, 100, 42, &[42])), runs(&[('-', 20), ('#', 1), ('-', 29)]));
+        // with fewer numbers than cells every number gets five of them
+        assert_eq!(range_bar(&game(1, 10, 3, &[3])), runs(&[('-', 10), ('#', 5), ('-', 35)]));
+        // the smallest range there is
+        assert_eq!(range_bar(&game(1, 2, 2, &[1])), runs(&[('-', 25), ('#', 25)]));
+        assert_eq!(range_bar(&game(0, u32::MAX, 0, &[0])), runs(&[('#', 1), ('-', 49)]));
+        assert_eq!(range_bar(&game(0, u32::MAX, u32::MAX, &[u32::MAX])), runs(&[('-', 49), ('#', 1)]));
+    }
+
+    #[test]
+    fn the_bar_always_shows_the_secret() {
+        for &(low, high) in &[(1, 2), (1, 7), (1, 49), (1, 50), (1, 51), (3, 137), (0, u32::MAX)] {
+            let span = u64::from(high - low) + 1;
+            for secret in [low, low + 1, low + (high - low) / 3, high - 1, high] {
+                let mut game = Game::new(secret, Config::new(low, high, None).unwrap());
+
+                // binary search, checking the bar after every guess
+                loop {
+                    let bar: Vec<char> = range_bar(&game).chars().collect();
+                    assert_eq!(bar.len(), BAR_WIDTH as usize);
+
+                    // the '#' cells are one unbroken block, and the secret's cell is in it
+                    let text: String = bar.iter().collect();
+                    assert_eq!(text.trim_matches('-').chars().filter(|&c| c == '-').count(), 0, "{}", text);
+                    // the last cell that starts at or before the secret
+                    let width = u64::from(BAR_WIDTH);
+                    let cell = ((u64::from(secret - low) + 1) * width).div_ceil(span) as usize - 1;
+                    assert_eq!(bar[cell], '#', "secret {} in {}..={}: {}", secret, low, high, text);
+
+                    if game.is_won() {
+                        break;
+                    }
+                    let (low, high) = game.narrowed_range();
+                    game.guess(low + (high - low) / 2);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn hints_only_appear_when_they_are_on() {
+        let script = "WRONG\nq\n";
+        let bands = ["Freezing", "Cold", "Warm", "Hot"];
+
+        let off = play(script, None);
+        assert!(!bands.iter().any(|band| off.contains(band)), "{}", off);
+
+        let on = play(script, Some(&Thresholds::default()));
+        assert!(bands.iter().any(|band| on.contains(band)), "{}", on);
+    }
+}
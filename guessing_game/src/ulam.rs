@@ -0,0 +1,341 @@
+// Ulam's game: the number game, except that the oracle may lie
+// up to `max_lies` of its Too small / Too large answers can be false; naming the number is always answered truthfully
+use std::fmt;
+use std::str::FromStr;
+
+use rand::distributions::Uniform;
+use rand::Rng;
+
+use crate::config::Config;
+use crate::game::Outcome;
+
+// how the oracle decides when to spend a lie
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LiePolicy {
+    // each answer is a lie with this probability (while there are lies left)
+    Random(f64),
+    // lie whenever the false answer leaves the player more numbers to consider than the true one
+    Adversarial,
+}
+
+impl fmt::Display for LiePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LiePolicy::Random(chance) => write!(f, "random ({:.0}% chance per answer)", chance * 100.0),
+            LiePolicy::Adversarial => write!(f, "adversarial"),
+        }
+    }
+}
+
+impl FromStr for LiePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<LiePolicy, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "random" => Ok(LiePolicy::Random(0.3)),
+            "adversarial" => Ok(LiePolicy::Adversarial),
+            _ => Err(format!("unknown liar '{}' (expected random or adversarial)", s)),
+        }
+    }
+}
+
+// one question and the answer that was given
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Answer {
+    pub guess: u32,
+    pub told: Outcome,
+    pub truth: Outcome,
+}
+
+impl Answer {
+    pub fn is_lie(&self) -> bool {
+        self.told != self.truth
+    }
+}
+
+fn compare(guess: u32, secret: u32) -> Outcome {
+    if guess < secret {
+        Outcome::TooSmall
+    } else if guess > secret {
+        Outcome::TooLarge
+    } else {
+        Outcome::Correct
+    }
+}
+
+fn flip(outcome: Outcome) -> Outcome {
+    match outcome {
+        Outcome::TooSmall => Outcome::TooLarge,
+        Outcome::TooLarge => Outcome::TooSmall,
+        Outcome::Correct => Outcome::Correct,
+    }
+}
+
+// how many of the answers would have been lies if the secret were `candidate`
+pub fn contradictions(answers: &[Answer], candidate: u32) -> u32 {
+    answers
+        .iter()
+        .filter(|answer| compare(answer.guess, candidate) != answer.told)
+        .count() as u32
+}
+
+// the numbers that could still be the secret (contradicted by at most max_lies answers),
+// as a list of inclusive (first, last) runs
+// every number between two guesses contradicts the same answers, so we only need to test one number per run
+pub fn candidates(answers: &[Answer], low: u32, high: u32, max_lies: u32) -> Vec<(u32, u32)> {
+    let mut points: Vec<u32> = answers
+        .iter()
+        .map(|answer| answer.guess)
+        .filter(|&guess| guess >= low && guess <= high)
+        .collect();
+    points.sort_unstable();
+    points.dedup();
+
+    // split low..=high into the runs between (and at) the guessed numbers
+    let mut runs = Vec::new();
+    let mut start = low;
+    for &point in &points {
+        if point > start {
+            runs.push((start, point - 1));
+        }
+        runs.push((point, point));
+        start = point.saturating_add(1);
+        if point == u32::MAX {
+            break;
+        }
+    }
+    if start <= high && points.last() != Some(&u32::MAX) {
+        runs.push((start, high));
+    }
+
+    runs.into_iter()
+        .filter(|&(first, _)| contradictions(answers, first) <= max_lies)
+        .collect()
+}
+
+// how many numbers the runs contain
+pub fn count(runs: &[(u32, u32)]) -> u64 {
+    runs.iter().map(|&(first, last)| u64::from(last - first) + 1).sum()
+}
+
+#[derive(Debug)]
+pub struct LyingGame {
+    secret: u32,
+    config: Config,
+    max_lies: u32,
+    policy: LiePolicy,
+    answers: Vec<Answer>,
+}
+
+impl LyingGame {
+    pub fn new(secret: u32, config: Config, max_lies: u32, policy: LiePolicy) -> LyingGame {
+        LyingGame {
+            secret,
+            config,
+            max_lies,
+            policy,
+            answers: Vec::new(),
+        }
+    }
+
+    pub fn random<R: Rng + ?Sized>(config: Config, max_lies: u32, policy: LiePolicy, rng: &mut R) -> LyingGame {
+        let secret = rng.sample(Uniform::new_inclusive(config.low, config.high));
+        LyingGame::new(secret, config, max_lies, policy)
+    }
+
+    // answer a guess - possibly with a lie
+    // the generator is only used by the Random policy
+    pub fn guess<R: Rng + ?Sized>(&mut self, guess: u32, rng: &mut R) -> Outcome {
+        let truth = compare(guess, self.secret);
+
+        let lie = truth != Outcome::Correct
+            && self.lies_left() > 0
+            && match self.policy {
+                LiePolicy::Random(chance) => rng.gen_bool(chance.clamp(0.0, 1.0)),
+                LiePolicy::Adversarial => self.lie_helps(guess, truth),
+            };
+
+        let told = if lie { flip(truth) } else { truth };
+        self.answers.push(Answer { guess, told, truth });
+        told
+    }
+
+    // would lying leave the player with more possible numbers than telling the truth?
+    fn lie_helps(&self, guess: u32, truth: Outcome) -> bool {
+        let left_after = |told: Outcome| {
+            let mut answers = self.answers.clone();
+            answers.push(Answer { guess, told, truth });
+            count(&candidates(&answers, self.config.low, self.config.high, self.max_lies))
+        };
+
+        left_after(flip(truth)) > left_after(truth)
+    }
+
+    pub fn lies_told(&self) -> u32 {
+        self.answers.iter().filter(|answer| answer.is_lie()).count() as u32
+    }
+
+    pub fn lies_left(&self) -> u32 {
+        self.max_lies - self.lies_told()
+    }
+
+    pub fn max_lies(&self) -> u32 {
+        self.max_lies
+    }
+
+    pub fn policy(&self) -> LiePolicy {
+        self.policy
+    }
+
+    pub fn answers(&self) -> &[Answer] {
+        &self.answers
+    }
+
+    pub fn secret(&self) -> u32 {
+        self.secret
+    }
+
+    pub fn low(&self) -> u32 {
+        self.config.low
+    }
+
+    pub fn high(&self) -> u32 {
+        self.config.high
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.answers.len() as u32
+    }
+
+    pub fn attempts_left(&self) -> Option<u32> {
+        self.config.max_attempts.map(|max| max.saturating_sub(self.attempts()))
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.answers.iter().any(|answer| answer.told == Outcome::Correct)
+    }
+
+    pub fn is_lost(&self) -> bool {
+        !self.is_won() && self.attempts_left() == Some(0)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.is_lost()
+    }
+}
+
+// a computer player for Ulam's game
+// it keeps every number that is contradicted by at most max_lies answers and always asks about the middle one
+// each wrong guess is contradicted by its own answer, so the pool of possible (number, lies) pairs always shrinks
+pub fn solve<R: Rng + ?Sized>(game: &mut LyingGame, rng: &mut R) -> bool {
+    while !game.is_over() {
+        let runs = candidates(game.answers(), game.low(), game.high(), game.max_lies());
+
+        // walk the runs to find the middle candidate
+        let mut middle = (count(&runs) - 1) / 2;
+        let mut guess = game.low();
+        for &(first, last) in &runs {
+            let size = u64::from(last - first) + 1;
+            if middle < size {
+                guess = first + middle as u32;
+                break;
+            }
+            middle -= size;
+        }
+
+        game.guess(guess, rng);
+    }
+
+    game.is_won()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session;
+
+    // an answer as the player heard it - candidates() never looks at the truth
+    fn told(guess: u32, told: Outcome) -> Answer {
+        Answer { guess, told, truth: told }
+    }
+
+    #[test]
+    fn contradictions_count_the_answers_a_secret_would_make_lies() {
+        let answers = [told(50, Outcome::TooSmall), told(75, Outcome::TooLarge)];
+
+        assert_eq!(contradictions(&answers, 60), 0);
+        assert_eq!(contradictions(&answers, 10), 1);
+        assert_eq!(contradictions(&answers, 50), 1);
+        assert_eq!(contradictions(&answers, 90), 1);
+    }
+
+    #[test]
+    fn with_no_lies_the_answers_are_simply_believed() {
+        let answers = [told(50, Outcome::TooSmall), told(60, Outcome::TooLarge)];
+        assert_eq!(candidates(&answers, 1, 100, 0), vec![(51, 59)]);
+
+        // a Correct answer leaves just the one number
+        assert_eq!(candidates(&[told(42, Outcome::Correct)], 1, 100, 0), vec![(42, 42)]);
+    }
+
+    #[test]
+    fn each_allowed_lie_lets_more_numbers_back_in() {
+        let answers = [told(50, Outcome::TooSmall), told(75, Outcome::TooLarge), told(60, Outcome::TooLarge)];
+
+        assert_eq!(candidates(&answers, 1, 100, 0), vec![(51, 59)]);
+        // 75 and everything above it contradict two answers, so one lie is not enough for them
+        assert_eq!(candidates(&answers, 1, 100, 1), vec![(1, 49), (50, 50), (51, 59), (60, 60), (61, 74)]);
+        assert_eq!(count(&candidates(&answers, 1, 100, 1)), 74);
+        assert_eq!(count(&candidates(&answers, 1, 100, 2)), 100);
+    }
+
+    #[test]
+    fn runs_reach_the_ends_of_the_u32_range() {
+        assert_eq!(count(&candidates(&[], 0, u32::MAX, 0)), 1 << 32);
+
+        let answers = [told(u32::MAX, Outcome::TooLarge)];
+        assert_eq!(candidates(&answers, 0, u32::MAX, 0), vec![(0, u32::MAX - 1)]);
+        assert_eq!(candidates(&answers, 0, u32::MAX, 1), vec![(0, u32::MAX - 1), (u32::MAX, u32::MAX)]);
+    }
+
+    // every policy, including one that lies at every chance it gets
+    fn policies() -> [LiePolicy; 3] {
+        [LiePolicy::Random(0.3), LiePolicy::Random(1.0), LiePolicy::Adversarial]
+    }
+
+    #[test]
+    fn no_policy_tells_more_lies_than_allowed() {
+        let config = Config::new(1, 30, None).unwrap();
+        let mut rng = session::rng_from_seed(Some(5));
+
+        for policy in policies() {
+            for max_lies in 0..=2 {
+                let mut game = LyingGame::new(17, config, max_lies, policy);
+                // guessing the same wrong number over and over gives the liar every chance
+                for _ in 0..10 {
+                    game.guess(3, &mut rng);
+                }
+                assert!(game.lies_told() <= max_lies, "{} told {} lies", policy, game.lies_told());
+                assert_eq!(game.lies_left(), max_lies - game.lies_told());
+            }
+        }
+    }
+
+    #[test]
+    fn solve_finds_every_secret_under_every_policy() {
+        let config = Config::new(1, 30, None).unwrap();
+        let mut rng = session::rng_from_seed(Some(9));
+
+        for policy in policies() {
+            for max_lies in 0..=2 {
+                for secret in 1..=30 {
+                    let mut game = LyingGame::new(secret, config, max_lies, policy);
+                    assert!(solve(&mut game, &mut rng), "{} with {} lies under {}", secret, max_lies, policy);
+                    assert!(game.lies_told() <= max_lies);
+                    // the name of the number itself is never lied about
+                    assert_eq!(game.answers().last().map(|answer| answer.told), Some(Outcome::Correct));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,637 @@
+// rule sets for the number game
+//
+// a GameVariant decides how a round is set up, what the player is asked on each turn, what they are told about
+// their answer and when the round is won or lost - the prompt loop in main.rs only reads lines and prints whatever
+// the variant answers, so the classic game and every other rule set share hints, transcripts, the TUI and scores
+// new rules are a new struct implementing the trait plus an entry in VariantKind
+//
+// the turn does not have to be a guess: in the reverse variant the computer guesses and the player's turn is
+// answering it, which is why play() gets the raw line and each variant parses it for itself
+use std::fmt;
+use std::str::FromStr;
+
+use rand::seq::index;
+use rand::RngCore;
+
+use crate::config::Config;
+use crate::game::{Game, Outcome};
+use crate::input::{self, Command, InputError};
+
+// how many numbers the multi-secret variant hides
+pub const MULTI_SECRETS: u32 = 3;
+
+// the rules of a round, shown once at the start of it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rules {
+    // one secret number, too small or too large
+    OneNumber { low: u32, high: u32 },
+    // one secret number, and guesses must stay inside what the answers so far allow
+    Shrinking { low: u32, high: u32 },
+    // several different secret numbers to find
+    Several { count: u32, low: u32, high: u32 },
+    // the player picks the number and the computer guesses it
+    Reverse { low: u32, high: u32 },
+}
+
+impl fmt::Display for Rules {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Rules::OneNumber { low, high } => write!(f, "I'm thinking of a number between {} and {}.", low, high),
+            Rules::Shrinking { low, high } => write!(
+                f,
+                "I'm thinking of a number between {} and {}.\nEvery answer shrinks the range, and you must guess inside it.",
+                low, high
+            ),
+            Rules::Several { count, low, high } => write!(
+                f,
+                "I'm thinking of {} different numbers between {} and {}. Find them all!\nA miss tells you how many are below and above your guess.",
+                count, low, high
+            ),
+            Rules::Reverse { low, high } => write!(
+                f,
+                "Think of a number between {} and {} and I will guess it.\nAnswer + if your number is bigger, - if it is smaller and = when I get it.",
+                low, high
+            ),
+        }
+    }
+}
+
+// what the player is asked to type on their turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prompt {
+    // a guess at the secret
+    Guess,
+    // how the computer's guess compares with the player's number
+    Judge(u32),
+}
+
+impl fmt::Display for Prompt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Prompt::Guess => write!(f, "Enter your guess: "),
+            Prompt::Judge(guess) => write!(f, "Is it {}? (+ bigger, - smaller, = yes): ", guess),
+        }
+    }
+}
+
+// what the player is told about their turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feedback {
+    // the classic answer: too small, too large or correct
+    Compare(Outcome),
+    // the classic answer, plus the range the secret is now known to be in
+    Narrowed { outcome: Outcome, low: u32, high: u32 },
+    // the guess found one of several secrets - `remaining` are still hidden
+    Found { remaining: u32 },
+    // the guess missed every secret: how many of the hidden ones are below and above it
+    Missed { below: u32, above: u32 },
+}
+
+impl fmt::Display for Feedback {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Feedback::Compare(Outcome::TooSmall) => write!(f, "Too small..."),
+            Feedback::Compare(Outcome::TooLarge) => write!(f, "Too large..."),
+            Feedback::Compare(Outcome::Correct) => write!(f, "Correct!"),
+            Feedback::Narrowed { outcome: Outcome::Correct, .. } => write!(f, "Correct!"),
+            Feedback::Narrowed { outcome, low, high } => {
+                write!(f, "{} it is between {} and {}.", Feedback::Compare(outcome), low, high)
+            }
+            Feedback::Found { remaining: 0 } => write!(f, "Found one - and that was the last!"),
+            Feedback::Found { remaining } => write!(f, "Found one! {} still hidden.", remaining),
+            Feedback::Missed { below, above } => write!(f, "Missed - {} below, {} above.", below, above),
+        }
+    }
+}
+
+// one set of rules
+pub trait GameVariant {
+    // start a round - `where Self: Sized` keeps this out of the trait object, so Box<dyn GameVariant> still works
+    fn setup(config: Config, rng: &mut dyn RngCore) -> Self
+    where
+        Self: Sized;
+
+    fn rules(&self) -> Rules;
+
+    // the range and attempt limit the round was set up with
+    fn config(&self) -> Config;
+
+    // what the player is asked for next
+    fn prompt(&self) -> Prompt {
+        Prompt::Guess
+    }
+
+    // play one line typed at the prompt - the caller has already checked that it is not 'quit'
+    // a line that does not make sense for this turn is an InputError, and does not use up an attempt
+    fn play(&mut self, line: &str) -> Result<Feedback, InputError>;
+
+    fn attempts(&self) -> u32;
+
+    // None means unlimited guesses
+    fn attempts_left(&self) -> Option<u32>;
+
+    fn is_won(&self) -> bool;
+
+    fn is_lost(&self) -> bool;
+
+    fn is_over(&self) -> bool {
+        self.is_won() || self.is_lost()
+    }
+
+    // what was being looked for, for the end of the round
+    fn reveal(&self) -> String;
+
+    // the single-secret Game behind the round, for the extras that only understand one secret number
+    // (hints, transcripts and the TUI's range bar) - variants that don't have one keep the default
+    fn game(&self) -> Option<&Game> {
+        None
+    }
+}
+
+// the guess on a line of input
+// giving up has already been dealt with by the caller, so here 'quit' is just something that is not a number
+fn parse_guess(line: &str, low: u32, high: u32) -> Result<u32, InputError> {
+    match input::parse_command(line, low, high)? {
+        Command::Guess(guess) => Ok(guess),
+        Command::Quit => Err(InputError::NotANumber(line.trim().to_string())),
+    }
+}
+
+// the original game: one secret, too small or too large
+pub struct Classic {
+    game: Game,
+}
+
+impl GameVariant for Classic {
+    fn setup(config: Config, rng: &mut dyn RngCore) -> Classic {
+        Classic {
+            game: Game::random(config, rng),
+        }
+    }
+
+    fn rules(&self) -> Rules {
+        Rules::OneNumber {
+            low: self.game.low(),
+            high: self.game.high(),
+        }
+    }
+
+    fn config(&self) -> Config {
+        *self.game.config()
+    }
+
+    fn play(&mut self, line: &str) -> Result<Feedback, InputError> {
+        let guess = parse_guess(line, self.game.low(), self.game.high())?;
+        Ok(Feedback::Compare(self.game.guess(guess)))
+    }
+
+    fn attempts(&self) -> u32 {
+        self.game.attempts()
+    }
+
+    fn attempts_left(&self) -> Option<u32> {
+        self.game.attempts_left()
+    }
+
+    fn is_won(&self) -> bool {
+        self.game.is_won()
+    }
+
+    fn is_lost(&self) -> bool {
+        self.game.is_lost()
+    }
+
+    fn reveal(&self) -> String {
+        self.game.secret().to_string()
+    }
+
+    fn game(&self) -> Option<&Game> {
+        Some(&self.game)
+    }
+}
+
+// the classic game, but every answer also shrinks the range and guesses outside it are refused
+pub struct Shrinking {
+    game: Game,
+}
+
+impl GameVariant for Shrinking {
+    fn setup(config: Config, rng: &mut dyn RngCore) -> Shrinking {
+        Shrinking {
+            game: Game::random(config, rng),
+        }
+    }
+
+    fn rules(&self) -> Rules {
+        Rules::Shrinking {
+            low: self.game.low(),
+            high: self.game.high(),
+        }
+    }
+
+    fn config(&self) -> Config {
+        *self.game.config()
+    }
+
+    // only guesses inside the narrowed range are allowed
+    fn play(&mut self, line: &str) -> Result<Feedback, InputError> {
+        let (low, high) = self.game.narrowed_range();
+        let guess = parse_guess(line, low, high)?;
+
+        let outcome = self.game.guess(guess);
+        let (low, high) = self.game.narrowed_range();
+        Ok(Feedback::Narrowed { outcome, low, high })
+    }
+
+    fn attempts(&self) -> u32 {
+        self.game.attempts()
+    }
+
+    fn attempts_left(&self) -> Option<u32> {
+        self.game.attempts_left()
+    }
+
+    fn is_won(&self) -> bool {
+        self.game.is_won()
+    }
+
+    fn is_lost(&self) -> bool {
+        self.game.is_lost()
+    }
+
+    fn reveal(&self) -> String {
+        self.game.secret().to_string()
+    }
+
+    fn game(&self) -> Option<&Game> {
+        Some(&self.game)
+    }
+}
+
+// several different secrets at once - find them all
+// a miss says how many of the secrets still hidden are below and above the guess
+pub struct MultiSecret {
+    config: Config,
+    // the secrets not found yet, and the ones that have been
+    hidden: Vec<u32>,
+    found: Vec<u32>,
+    attempts: u32,
+    // the attempt limit is per secret, so it scales with how many there are
+    max_attempts: Option<u32>,
+}
+
+impl GameVariant for MultiSecret {
+    fn setup(config: Config, rng: &mut dyn RngCore) -> MultiSecret {
+        // a tiny range may not have room for every secret
+        let size = (config.high - config.low) as usize + 1;
+        let count = size.min(MULTI_SECRETS as usize);
+
+        // index::sample picks `count` different positions, so the secrets never repeat
+        let mut hidden: Vec<u32> = index::sample(rng, size, count)
+            .into_iter()
+            .map(|offset| config.low + offset as u32)
+            .collect();
+        hidden.sort_unstable();
+
+        MultiSecret {
+            config,
+            max_attempts: config.max_attempts.map(|max| max.saturating_mul(count as u32)),
+            hidden,
+            found: Vec::new(),
+            attempts: 0,
+        }
+    }
+
+    fn rules(&self) -> Rules {
+        Rules::Several {
+            count: (self.hidden.len() + self.found.len()) as u32,
+            low: self.config.low,
+            high: self.config.high,
+        }
+    }
+
+    fn config(&self) -> Config {
+        self.config
+    }
+
+    fn play(&mut self, line: &str) -> Result<Feedback, InputError> {
+        let guess = parse_guess(line, self.config.low, self.config.high)?;
+        self.attempts += 1;
+
+        match self.hidden.iter().position(|&secret| secret == guess) {
+            Some(index) => {
+                self.found.push(self.hidden.remove(index));
+                Ok(Feedback::Found {
+                    remaining: self.hidden.len() as u32,
+                })
+            }
+            None => {
+                let below = self.hidden.iter().filter(|&&secret| secret < guess).count() as u32;
+                Ok(Feedback::Missed {
+                    below,
+                    above: self.hidden.len() as u32 - below,
+                })
+            }
+        }
+    }
+
+    fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    fn attempts_left(&self) -> Option<u32> {
+        self.max_attempts.map(|max| max.saturating_sub(self.attempts))
+    }
+
+    fn is_won(&self) -> bool {
+        self.hidden.is_empty()
+    }
+
+    fn is_lost(&self) -> bool {
+        !self.is_won() && self.attempts_left() == Some(0)
+    }
+
+    fn reveal(&self) -> String {
+        let mut all: Vec<u32> = self.found.iter().chain(self.hidden.iter()).cloned().collect();
+        all.sort_unstable();
+        let all: Vec<String> = all.iter().map(|secret| secret.to_string()).collect();
+        all.join(", ")
+    }
+}
+
+// the player thinks of a number and the computer guesses it, halving what is left each time
+// the player's turn is answering the guess - answers that contradict each other are refused
+pub struct Reverse {
+    config: Config,
+    // the numbers the answers so far still allow (inclusive)
+    low: u32,
+    high: u32,
+    // the guess the player is being asked about
+    guess: u32,
+    attempts: u32,
+    found: bool,
+}
+
+// the same guess as solver::BinarySearch, so the computer never needs more than solver::worst_case guesses
+fn middle(low: u32, high: u32) -> u32 {
+    low + (high - low) / 2
+}
+
+impl GameVariant for Reverse {
+    // nothing is random - the player has the secret
+    fn setup(config: Config, _rng: &mut dyn RngCore) -> Reverse {
+        Reverse {
+            config,
+            low: config.low,
+            high: config.high,
+            guess: middle(config.low, config.high),
+            attempts: 0,
+            found: false,
+        }
+    }
+
+    fn rules(&self) -> Rules {
+        Rules::Reverse {
+            low: self.config.low,
+            high: self.config.high,
+        }
+    }
+
+    fn config(&self) -> Config {
+        self.config
+    }
+
+    fn prompt(&self) -> Prompt {
+        Prompt::Judge(self.guess)
+    }
+
+    fn play(&mut self, line: &str) -> Result<Feedback, InputError> {
+        // the answer says how the guess compares with the player's number, just like an Outcome does
+        let outcome = input::parse_answer(line)?;
+
+        // bigger than the top of what is left (or smaller than the bottom) can't be true of any number
+        match outcome {
+            Outcome::TooSmall if self.guess >= self.high => return Err(InputError::Contradiction),
+            Outcome::TooLarge if self.guess <= self.low => return Err(InputError::Contradiction),
+            _ => {}
+        }
+
+        self.attempts += 1;
+        match outcome {
+            Outcome::TooSmall => self.low = self.guess + 1,
+            Outcome::TooLarge => self.high = self.guess - 1,
+            Outcome::Correct => {
+                self.found = true;
+                self.low = self.guess;
+                self.high = self.guess;
+            }
+        }
+        self.guess = middle(self.low, self.high);
+
+        Ok(Feedback::Narrowed {
+            outcome,
+            low: self.low,
+            high: self.high,
+        })
+    }
+
+    fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    fn attempts_left(&self) -> Option<u32> {
+        self.config.max_attempts.map(|max| max.saturating_sub(self.attempts))
+    }
+
+    fn is_won(&self) -> bool {
+        self.found
+    }
+
+    fn is_lost(&self) -> bool {
+        !self.found && self.attempts_left() == Some(0)
+    }
+
+    // the computer never learns the number unless it finds it, so this is what the answers narrowed it down to
+    fn reveal(&self) -> String {
+        if self.low == self.high {
+            self.low.to_string()
+        } else {
+            format!("{}-{}", self.low, self.high)
+        }
+    }
+}
+
+// the registered variants, so they can be chosen by name on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VariantKind {
+    #[default]
+    Classic,
+    Shrinking,
+    MultiSecret,
+    Reverse,
+}
+
+impl VariantKind {
+    pub const ALL: [VariantKind; 4] = [
+        VariantKind::Classic,
+        VariantKind::Shrinking,
+        VariantKind::MultiSecret,
+        VariantKind::Reverse,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            VariantKind::Classic => "classic",
+            VariantKind::Shrinking => "shrink",
+            VariantKind::MultiSecret => "multi",
+            VariantKind::Reverse => "reverse",
+        }
+    }
+
+    // whether a round has a single secret Game behind it, which hints, transcripts and scores need
+    pub fn one_secret(&self) -> bool {
+        match *self {
+            VariantKind::Classic | VariantKind::Shrinking => true,
+            VariantKind::MultiSecret | VariantKind::Reverse => false,
+        }
+    }
+
+    // whether the player is the one guessing, rather than answering the computer's guesses
+    pub fn player_guesses(&self) -> bool {
+        *self != VariantKind::Reverse
+    }
+
+    // set up a round of this variant
+    pub fn setup(&self, config: Config, rng: &mut dyn RngCore) -> Box<dyn GameVariant> {
+        match *self {
+            VariantKind::Classic => Box::new(Classic::setup(config, rng)),
+            VariantKind::Shrinking => Box::new(Shrinking::setup(config, rng)),
+            VariantKind::MultiSecret => Box::new(MultiSecret::setup(config, rng)),
+            VariantKind::Reverse => Box::new(Reverse::setup(config, rng)),
+        }
+    }
+}
+
+impl fmt::Display for VariantKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for VariantKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<VariantKind, String> {
+        VariantKind::ALL
+            .iter()
+            .find(|kind| kind.name() == s.to_ascii_lowercase())
+            .cloned()
+            .ok_or_else(|| format!("unknown variant '{}' (expected classic, shrink, multi or reverse)", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn config() -> Config {
+        Config::new(1, 100, Some(10)).unwrap()
+    }
+
+    #[test]
+    fn classic_answers_too_small_too_large_and_correct() {
+        let mut classic = Classic {
+            game: Game::new(42, config()),
+        };
+
+        assert_eq!(classic.play("10"), Ok(Feedback::Compare(Outcome::TooSmall)));
+        assert_eq!(classic.play("90\n"), Ok(Feedback::Compare(Outcome::TooLarge)));
+        assert_eq!(classic.play("42"), Ok(Feedback::Compare(Outcome::Correct)));
+        assert!(classic.is_won());
+        assert_eq!(classic.attempts(), 3);
+    }
+
+    #[test]
+    fn bad_input_does_not_use_an_attempt() {
+        let mut classic = Classic {
+            game: Game::new(42, config()),
+        };
+
+        assert_eq!(classic.play("fifty"), Err(InputError::NotANumber("fifty".to_string())));
+        assert_eq!(classic.attempts(), 0);
+    }
+
+    #[test]
+    fn shrinking_refuses_guesses_outside_the_narrowed_range() {
+        let mut shrinking = Shrinking {
+            game: Game::new(42, config()),
+        };
+
+        assert_eq!(
+            shrinking.play("30"),
+            Ok(Feedback::Narrowed { outcome: Outcome::TooSmall, low: 31, high: 100 })
+        );
+        assert!(shrinking.play("20").is_err());
+        assert_eq!(shrinking.attempts(), 1);
+    }
+
+    #[test]
+    fn multi_secret_is_won_once_every_secret_is_found() {
+        let mut multi = MultiSecret::setup(config(), &mut ChaCha8Rng::seed_from_u64(1));
+        let secrets = multi.hidden.clone();
+        assert_eq!(secrets.len(), MULTI_SECRETS as usize);
+        // three secrets get three times the attempts
+        assert_eq!(multi.attempts_left(), Some(30));
+
+        for (index, secret) in secrets.iter().enumerate() {
+            assert!(!multi.is_won());
+            let remaining = (secrets.len() - index - 1) as u32;
+            assert_eq!(multi.play(&secret.to_string()), Ok(Feedback::Found { remaining }));
+        }
+
+        assert!(multi.is_won());
+    }
+
+    #[test]
+    fn reverse_finds_the_number_from_the_players_answers() {
+        let mut reverse = Reverse::setup(config(), &mut ChaCha8Rng::seed_from_u64(1));
+        let secret = 71;
+
+        while !reverse.is_over() {
+            let guess = match reverse.prompt() {
+                Prompt::Judge(guess) => guess,
+                Prompt::Guess => panic!("the computer should be guessing"),
+            };
+            let answer = if guess < secret {
+                "+"
+            } else if guess > secret {
+                "-"
+            } else {
+                "="
+            };
+            reverse.play(answer).unwrap();
+        }
+
+        assert!(reverse.is_won());
+        assert_eq!(reverse.reveal(), "71");
+        assert!(reverse.attempts() <= crate::solver::worst_case(1, 100));
+    }
+
+    #[test]
+    fn reverse_refuses_contradictory_answers() {
+        let mut reverse = Reverse::setup(Config::new(1, 3, None).unwrap(), &mut ChaCha8Rng::seed_from_u64(1));
+        assert_eq!(reverse.prompt(), Prompt::Judge(2));
+
+        reverse.play("+").unwrap();
+        assert_eq!(reverse.prompt(), Prompt::Judge(3));
+
+        // bigger than 3 is outside the range, and smaller than 3 was ruled out by the first answer
+        assert_eq!(reverse.play("+"), Err(InputError::Contradiction));
+        assert_eq!(reverse.play("-"), Err(InputError::Contradiction));
+        assert_eq!(reverse.play("?"), Err(InputError::NotAnAnswer));
+        assert_eq!(reverse.attempts(), 1);
+    }
+}
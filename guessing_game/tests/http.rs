@@ -0,0 +1,197 @@
+// integration tests for the HTTP API: a real server on a loopback port, talked to with plain TcpStreams
+// expiry and the size limits are checked on Sessions and read_request directly, without a network or a wait
+extern crate guessing_game;
+
+use std::io::{Cursor, ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use guessing_game::http;
+
+// start a server on a free port (port 0 lets the OS pick one) and return its address
+fn start(timeout: Duration) -> SocketAddr {
+    let listener = http::bind("127.0.0.1:0").expect("bind to loopback");
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || http::serve(listener, timeout));
+    address
+}
+
+// send one request and return the status code and the body
+fn send(address: SocketAddr, method: &str, path: &str, body: &str) -> (u16, String) {
+    let mut stream = TcpStream::connect(address).unwrap();
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+        method,
+        path,
+        body.len(),
+        body
+    )
+    .unwrap();
+
+    // the server closes the connection after every response, so read to the end
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    let status = response.split_whitespace().nth(1).unwrap().parse().unwrap();
+    let body = response.split("\r\n\r\n").nth(1).unwrap_or("").to_string();
+    (status, body)
+}
+
+// the raw text of a top-level number or string field, e.g. field(body, "id") -> "1"
+fn field(body: &str, name: &str) -> String {
+    let key = format!("\"{}\":", name);
+    let start = body.find(&key).unwrap_or_else(|| panic!("no {} in {}", name, body)) + key.len();
+    let rest = &body[start..];
+    let end = rest.find([',', '}']).unwrap();
+    rest[..end].trim_matches('"').to_string()
+}
+
+// find the number with binary search through the API, returning the final body
+fn play_to_the_end(address: SocketAddr, id: &str) -> String {
+    let (mut low, mut high) = (1, 100);
+    loop {
+        let guess = low + (high - low) / 2;
+        let (status, body) = send(address, "POST", &format!("/sessions/{}/guesses", id), &format!("{{\"guess\": {}}}", guess));
+        assert_eq!(status, 200, "{}", body);
+
+        match field(&body, "outcome").as_str() {
+            "too_small" => low = guess + 1,
+            "too_large" => high = guess - 1,
+            "correct" => return body,
+            other => panic!("unexpected outcome {}", other),
+        }
+    }
+}
+
+#[test]
+fn play_a_whole_game() {
+    let address = start(Duration::from_secs(60));
+
+    let (status, body) = send(address, "POST", "/sessions", "{\"low\": 1, \"high\": 100, \"attempts\": null}");
+    assert_eq!(status, 201, "{}", body);
+    assert_eq!(field(&body, "status"), "playing");
+    assert!(!body.contains("secret"), "the secret leaked: {}", body);
+    let id = field(&body, "id");
+
+    let finished = play_to_the_end(address, &id);
+    assert_eq!(field(&finished, "status"), "won");
+
+    let (status, body) = send(address, "GET", &format!("/sessions/{}", id), "");
+    assert_eq!(status, 200);
+    assert_eq!(field(&body, "status"), "won");
+    assert_eq!(field(&body, "attempts"), field(&finished, "attempts"));
+    assert!(body.contains("\"secret\":"));
+
+    // the game is over, so more guesses are refused
+    let (status, _) = send(address, "POST", &format!("/sessions/{}/guesses", id), "{\"guess\": 1}");
+    assert_eq!(status, 409);
+}
+
+#[test]
+fn the_same_seed_gives_the_same_secret() {
+    let address = start(Duration::from_secs(60));
+
+    let secrets: Vec<String> = (0..2)
+        .map(|_| {
+            let (_, body) = send(address, "POST", "/sessions", "{\"seed\": 42}");
+            field(&play_to_the_end(address, &field(&body, "id")), "secret")
+        })
+        .collect();
+
+    assert_eq!(secrets[0], secrets[1]);
+}
+
+#[test]
+fn running_out_of_attempts_loses() {
+    let address = start(Duration::from_secs(60));
+
+    let (_, body) = send(address, "POST", "/sessions", "{\"low\": 1, \"high\": 10, \"attempts\": 1, \"seed\": 3}");
+    let id = field(&body, "id");
+    assert_eq!(field(&body, "attempts_left"), "1");
+
+    // one of these two guesses must be wrong
+    let path = format!("/sessions/{}/guesses", id);
+    let (_, first) = send(address, "POST", &path, "{\"guess\": 1}");
+    let body = if field(&first, "outcome") == "correct" {
+        let (_, body) = send(address, "POST", "/sessions", "{\"low\": 1, \"high\": 10, \"attempts\": 1, \"seed\": 3}");
+        send(address, "POST", &format!("/sessions/{}/guesses", field(&body, "id")), "{\"guess\": 2}").1
+    } else {
+        first
+    };
+
+    assert_eq!(field(&body, "status"), "lost");
+    assert_eq!(field(&body, "attempts_left"), "0");
+}
+
+#[test]
+fn bad_requests_are_rejected() {
+    let address = start(Duration::from_secs(60));
+
+    assert_eq!(send(address, "GET", "/sessions/99", "").0, 404);
+    assert_eq!(send(address, "GET", "/sessions/abc", "").0, 404);
+    assert_eq!(send(address, "GET", "/elsewhere", "").0, 404);
+    assert_eq!(send(address, "DELETE", "/sessions", "").0, 405);
+    assert_eq!(send(address, "POST", "/sessions", "not json").0, 400);
+    assert_eq!(send(address, "POST", "/sessions", "{\"low\": 10, \"high\": 1}").0, 400);
+    assert_eq!(send(address, "POST", "/sessions", "{\"colour\": 1}").0, 400);
+
+    let (_, body) = send(address, "POST", "/sessions", "");
+    let path = format!("/sessions/{}/guesses", field(&body, "id"));
+    assert_eq!(send(address, "POST", &path, "{\"guess\": 0}").0, 400);
+    assert_eq!(send(address, "POST", &path, "{\"guess\": 101}").0, 400);
+    assert_eq!(send(address, "POST", &path, "{\"guess\": -5}").0, 400);
+    assert_eq!(send(address, "POST", &path, "{}").0, 400);
+
+    let (status, body) = send(address, "POST", &path, "{\"guess\": \"fifty\"}");
+    assert_eq!(status, 400);
+    assert!(body.contains("\"error\":"));
+}
+
+// expiry is checked straight against Sessions with made-up times, so the test never has to wait
+#[test]
+fn sessions_expire_when_left_alone() {
+    let mut sessions = http::Sessions::new(Duration::from_secs(60));
+    let request = |method: &str, path: &str| http::Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        body: String::new(),
+    };
+
+    let created = sessions.handle(&request("POST", "/sessions"), Duration::from_secs(0));
+    let path = format!("/sessions/{}", field(&created.body, "id"));
+
+    // using a session keeps it alive, so three visits 50 seconds apart outlast the 60 second timeout
+    for visit in 1..=3 {
+        let response = sessions.handle(&request("GET", &path), Duration::from_secs(50 * visit));
+        assert_eq!(response.status, 200);
+    }
+
+    // exactly the timeout after the last visit is still in time
+    sessions.expire(Duration::from_secs(210));
+    assert_eq!(sessions.len(), 1);
+
+    sessions.expire(Duration::from_secs(211));
+    assert!(sessions.is_empty());
+    assert_eq!(sessions.handle(&request("GET", &path), Duration::from_secs(211)).status, 404);
+}
+
+#[test]
+fn oversized_headers_are_refused() {
+    let request = format!("GET /sessions/1 HTTP/1.1\r\nX-Padding: {}\r\n\r\n", "a".repeat(100_000));
+    let error = http::read_request(&mut Cursor::new(request)).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+    // a request line with no end is cut off too
+    let error = http::read_request(&mut Cursor::new("G".repeat(100_000))).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+    let request = http::read_request(&mut Cursor::new("GET /sessions/1?x=1 HTTP/1.1\r\nHost: a\r\n\r\n")).unwrap();
+    assert_eq!((request.method.as_str(), request.path.as_str()), ("GET", "/sessions/1"));
+}
+
+#[test]
+fn only_loopback_addresses_are_allowed() {
+    assert!(http::bind("0.0.0.0:0").is_err());
+}
@@ -0,0 +1,160 @@
+// integration tests for the multiplayer server: a real server on a loopback port and a few clients talking to it
+// the line limit is also checked on read_line directly, with a Cursor standing in for the connection
+extern crate guessing_game;
+
+use std::io::{BufRead, BufReader, Cursor, ErrorKind, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use guessing_game::config::Config;
+use guessing_game::server::{self, PlayMode, MAX_LINE};
+
+// start a server on a free port with a seeded secret and return its address
+fn start(mode: PlayMode) -> SocketAddr {
+    let listener = server::bind("127.0.0.1:0").expect("bind to loopback");
+    let address = listener.local_addr().unwrap();
+    let config = Config::new(1, 100, None).unwrap();
+    thread::spawn(move || server::serve(listener, config, mode, Some(7)));
+    address
+}
+
+struct Client {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Client {
+    // connect and read the welcome, the help line and the round intro
+    fn join(address: SocketAddr, name: &str) -> Client {
+        let stream = TcpStream::connect(address).unwrap();
+        // a missing line fails the test instead of hanging it
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let mut client = Client {
+            writer: stream.try_clone().unwrap(),
+            reader: BufReader::new(stream),
+        };
+
+        assert!(client.line().starts_with(&format!("Welcome, {}!", name)));
+        assert!(client.line().starts_with("Type a number to guess"));
+        assert_eq!(client.line(), "I'm thinking of a number between 1 and 100.");
+        client
+    }
+
+    fn say(&mut self, text: &str) {
+        writeln!(self.writer, "{}", text).unwrap();
+    }
+
+    fn line(&mut self) -> String {
+        let mut line = String::new();
+        self.reader.read_line(&mut line).expect("a line from the server");
+        line.trim_end().to_string()
+    }
+
+    fn expect(&mut self, line: &str) {
+        assert_eq!(self.line(), line);
+    }
+}
+
+#[test]
+fn everyone_hears_about_every_guess_in_a_race() {
+    let address = start(PlayMode::Race);
+    let mut alice = Client::join(address, "player1");
+    let mut bob = Client::join(address, "player2");
+    alice.expect("player2 joined the game");
+
+    alice.say("name alice");
+    alice.expect("player1 is now called alice");
+    bob.expect("player1 is now called alice");
+
+    // an invalid guess is only answered to whoever sent it
+    bob.say("lots");
+    bob.expect("'lots' is not a number.");
+
+    let (mut low, mut high, mut attempts) = (1, 100, 0);
+    loop {
+        let guess = low + (high - low) / 2;
+        attempts += 1;
+        alice.say(&guess.to_string());
+
+        let reply = alice.line();
+        let outcome = match reply.as_str() {
+            "Too small" => "too small",
+            "Too large" => "too large",
+            "Correct!" => "correct",
+            other => panic!("unexpected reply {}", other),
+        };
+        bob.expect(&format!("alice guessed {}: {}", guess, outcome));
+
+        match outcome {
+            "too small" => low = guess + 1,
+            "too large" => high = guess - 1,
+            _ => {
+                let found = format!("alice found the number {} after {} guesses!", guess, attempts);
+                for client in [&mut alice, &mut bob] {
+                    client.expect(&found);
+                    client.expect("New round! I'm thinking of a number between 1 and 100.");
+                }
+                break;
+            }
+        }
+    }
+
+    alice.say("quit");
+    bob.expect("alice left the game");
+}
+
+#[test]
+fn players_take_turns() {
+    let address = start(PlayMode::Turns);
+    let mut first = Client::join(address, "player1");
+    first.expect("Your turn!");
+    let mut second = Client::join(address, "player2");
+    second.expect("It's player1's turn");
+    first.expect("player2 joined the game");
+
+    second.say("50");
+    second.expect("Not your turn - waiting for player1");
+
+    // when the player whose turn it is leaves, the turn moves on
+    first.say("quit");
+    second.expect("player1 left the game");
+    second.expect("Your turn!");
+}
+
+#[test]
+fn an_endless_line_gets_the_client_disconnected() {
+    let address = start(PlayMode::Race);
+    let mut watcher = Client::join(address, "player1");
+    let mut flooder = Client::join(address, "player2");
+    watcher.expect("player2 joined the game");
+
+    flooder.writer.write_all(&vec![b'7'; MAX_LINE * 4]).unwrap();
+    watcher.expect("player2 left the game");
+
+    // the server hangs up - reading gives end of file, or a reset because the rest of the flood was never read
+    let mut rest = String::new();
+    match flooder.reader.read_line(&mut rest) {
+        Ok(read) => assert_eq!(read, 0, "{}", rest),
+        Err(e) => assert_eq!(e.kind(), ErrorKind::ConnectionReset),
+    }
+}
+
+#[test]
+fn read_line_stops_at_the_limit() {
+    let text = format!("42\r\nname bob\n{}\n", "x".repeat(MAX_LINE - 1));
+    let mut reader = Cursor::new(text);
+    assert_eq!(server::read_line(&mut reader).unwrap().as_deref(), Some("42"));
+    assert_eq!(server::read_line(&mut reader).unwrap().as_deref(), Some("name bob"));
+    // exactly MAX_LINE bytes with the newline is still fine
+    assert_eq!(server::read_line(&mut reader).unwrap().map(|line| line.len()), Some(MAX_LINE - 1));
+    assert_eq!(server::read_line(&mut reader).unwrap(), None);
+
+    let mut reader = Cursor::new("x".repeat(MAX_LINE));
+    assert_eq!(server::read_line(&mut reader).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // the last line does not need a newline, but it does need to be UTF-8
+    assert_eq!(server::read_line(&mut Cursor::new("quit")).unwrap().as_deref(), Some("quit"));
+    let mut reader = Cursor::new(&b"4\xff2\n"[..]);
+    assert_eq!(server::read_line(&mut reader).unwrap_err().kind(), ErrorKind::InvalidData);
+}
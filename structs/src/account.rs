@@ -0,0 +1,326 @@
+// the sign-in lifecycle of a User
+//
+// instead of separate `active` and `logged_in` flags (which allow nonsense like "logged in but deactivated")
+// an account is always in exactly one AccountState, and only these moves are possible:
+//
+//   SignedOut   --sign_in-->      SignedIn      (right password)
+//   SignedOut   --sign_in-->      Locked        (the MAX_FAILED_ATTEMPTS'th wrong password in a row)
+//   SignedIn    --sign_out-->     SignedOut
+//   Locked      --unlock-->       SignedOut
+//   any but Deactivated --deactivate--> Deactivated
+//   Deactivated --reactivate-->   SignedOut (or back to Locked, if it was locked before)
+use std::fmt;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+use crate::user::User;
+
+// wrong passwords in a row before the account is locked
+pub const MAX_FAILED_ATTEMPTS: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountState {
+    SignedOut,
+    SignedIn,
+    // too many wrong passwords - nobody can sign in until it is unlocked
+    Locked,
+    Deactivated,
+}
+
+impl AccountState {
+    pub const ALL: [AccountState; 4] = [
+        AccountState::SignedOut,
+        AccountState::SignedIn,
+        AccountState::Locked,
+        AccountState::Deactivated,
+    ];
+
+    // the name used when the state is saved
+    pub fn name(&self) -> &'static str {
+        match *self {
+            AccountState::SignedOut => "signed_out",
+            AccountState::SignedIn => "signed_in",
+            AccountState::Locked => "locked",
+            AccountState::Deactivated => "deactivated",
+        }
+    }
+}
+
+impl fmt::Display for AccountState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AccountState::SignedOut => write!(f, "signed out"),
+            AccountState::SignedIn => write!(f, "signed in"),
+            AccountState::Locked => write!(f, "locked"),
+            AccountState::Deactivated => write!(f, "deactivated"),
+        }
+    }
+}
+
+impl FromStr for AccountState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<AccountState, String> {
+        AccountState::ALL
+            .iter()
+            .find(|state| state.name() == s)
+            .cloned()
+            .ok_or_else(|| format!("unknown account state '{}'", s))
+    }
+}
+
+// why a lifecycle method refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountError {
+    WrongPassword { attempts_left: u32 },
+    // the account is locked - either it already was, or this attempt locked it
+    Locked,
+    Deactivated,
+    AlreadySignedIn,
+    NotSignedIn,
+    AlreadyDeactivated,
+    NotDeactivated,
+    NotLocked,
+}
+
+impl fmt::Display for AccountError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AccountError::WrongPassword { attempts_left } => {
+                write!(f, "wrong password ({} more tries before the account is locked)", attempts_left)
+            }
+            AccountError::Locked => write!(f, "the account is locked after too many wrong passwords"),
+            AccountError::Deactivated => write!(f, "the account is deactivated"),
+            AccountError::AlreadySignedIn => write!(f, "already signed in"),
+            AccountError::NotSignedIn => write!(f, "not signed in"),
+            AccountError::AlreadyDeactivated => write!(f, "the account is already deactivated"),
+            AccountError::NotDeactivated => write!(f, "the account is not deactivated"),
+            AccountError::NotLocked => write!(f, "the account is not locked"),
+        }
+    }
+}
+
+// a second impl block for User - Rust lets a type's methods be split over several blocks and files
+impl User {
+    pub fn sign_in(&mut self, password: &str) -> Result<(), AccountError> {
+        match self.state {
+            AccountState::SignedOut => {}
+            AccountState::SignedIn => return Err(AccountError::AlreadySignedIn),
+            AccountState::Locked => return Err(AccountError::Locked),
+            AccountState::Deactivated => return Err(AccountError::Deactivated),
+        }
+
+        if !self.verify_password(password) {
+            self.failed_attempts += 1;
+            if self.failed_attempts >= MAX_FAILED_ATTEMPTS {
+                self.state = AccountState::Locked;
+                return Err(AccountError::Locked);
+            }
+            return Err(AccountError::WrongPassword {
+                attempts_left: MAX_FAILED_ATTEMPTS - self.failed_attempts,
+            });
+        }
+
+        self.failed_attempts = 0;
+        self.sign_in_count += 1;
+        self.last_sign_in = Some(SystemTime::now());
+        self.state = AccountState::SignedIn;
+        Ok(())
+    }
+
+    pub fn sign_out(&mut self) -> Result<(), AccountError> {
+        if self.state != AccountState::SignedIn {
+            return Err(AccountError::NotSignedIn);
+        }
+
+        self.last_sign_out = Some(SystemTime::now());
+        self.state = AccountState::SignedOut;
+        Ok(())
+    }
+
+    // deactivating a signed in account signs it out first
+    pub fn deactivate(&mut self) -> Result<(), AccountError> {
+        match self.state {
+            AccountState::Deactivated => return Err(AccountError::AlreadyDeactivated),
+            AccountState::SignedIn => self.sign_out()?,
+            AccountState::SignedOut | AccountState::Locked => {}
+        }
+
+        self.deactivated_at = Some(SystemTime::now());
+        self.state = AccountState::Deactivated;
+        Ok(())
+    }
+
+    // a lock survives deactivating and reactivating - only unlock() clears it
+    pub fn reactivate(&mut self) -> Result<(), AccountError> {
+        if self.state != AccountState::Deactivated {
+            return Err(AccountError::NotDeactivated);
+        }
+
+        self.deactivated_at = None;
+        self.state = if self.failed_attempts >= MAX_FAILED_ATTEMPTS {
+            AccountState::Locked
+        } else {
+            AccountState::SignedOut
+        };
+        Ok(())
+    }
+
+    pub fn unlock(&mut self) -> Result<(), AccountError> {
+        if self.state != AccountState::Locked {
+            return Err(AccountError::NotLocked);
+        }
+
+        self.failed_attempts = 0;
+        self.state = AccountState::SignedOut;
+        Ok(())
+    }
+
+    pub fn state(&self) -> AccountState {
+        self.state
+    }
+
+    // everything except a deactivated account counts as active
+    pub fn is_active(&self) -> bool {
+        self.state != AccountState::Deactivated
+    }
+
+    pub fn is_logged_in(&self) -> bool {
+        self.state == AccountState::SignedIn
+    }
+
+    pub fn sign_in_count(&self) -> u64 {
+        self.sign_in_count
+    }
+
+    // wrong passwords since the last successful sign in (or unlock)
+    pub fn failed_attempts(&self) -> u32 {
+        self.failed_attempts
+    }
+
+    pub fn last_sign_in(&self) -> Option<SystemTime> {
+        self.last_sign_in
+    }
+
+    pub fn last_sign_out(&self) -> Option<SystemTime> {
+        self.last_sign_out
+    }
+
+    pub fn deactivated_at(&self) -> Option<SystemTime> {
+        self.deactivated_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSWORD: &str = "correct-horse-42";
+
+    fn user() -> User {
+        User::builder()
+            .name("Test User")
+            .email("test@example.com")
+            .age(30)
+            .username("tester")
+            .password(PASSWORD)
+            .build()
+            .expect("a valid user")
+    }
+
+    // every wrong password but the last one leaves the account signed out
+    fn lock(user: &mut User) {
+        for _ in 1..MAX_FAILED_ATTEMPTS {
+            user.sign_in("wrong-password-1").unwrap_err();
+        }
+        assert_eq!(user.sign_in("wrong-password-1"), Err(AccountError::Locked));
+    }
+
+    #[test]
+    fn wrong_passwords_count_down_to_a_lock() {
+        let mut user = user();
+
+        for tried in 1..MAX_FAILED_ATTEMPTS {
+            let attempts_left = MAX_FAILED_ATTEMPTS - tried;
+            assert_eq!(user.sign_in("wrong-password-1"), Err(AccountError::WrongPassword { attempts_left }));
+            assert_eq!(user.state(), AccountState::SignedOut);
+            assert_eq!(user.failed_attempts(), tried);
+        }
+
+        assert_eq!(user.sign_in("wrong-password-1"), Err(AccountError::Locked));
+        assert_eq!(user.state(), AccountState::Locked);
+
+        // once locked, even the right password is refused
+        assert_eq!(user.sign_in(PASSWORD), Err(AccountError::Locked));
+        assert_eq!(user.sign_in_count(), 0);
+    }
+
+    #[test]
+    fn signing_in_resets_the_failed_attempts() {
+        let mut user = user();
+
+        user.sign_in("wrong-password-1").unwrap_err();
+        user.sign_in("wrong-password-1").unwrap_err();
+        user.sign_in(PASSWORD).unwrap();
+
+        assert_eq!(user.failed_attempts(), 0);
+        assert_eq!(user.sign_in_count(), 1);
+        assert!(user.last_sign_in().is_some());
+        assert!(user.is_logged_in());
+    }
+
+    #[test]
+    fn deactivating_a_signed_in_account_signs_it_out() {
+        let mut user = user();
+        user.sign_in(PASSWORD).unwrap();
+
+        user.deactivate().unwrap();
+
+        assert_eq!(user.state(), AccountState::Deactivated);
+        assert!(user.last_sign_out().is_some());
+        assert!(user.deactivated_at().is_some());
+        assert!(!user.is_active());
+    }
+
+    #[test]
+    fn a_lock_survives_reactivating_until_unlocked() {
+        let mut user = user();
+        lock(&mut user);
+
+        user.deactivate().unwrap();
+        user.reactivate().unwrap();
+        assert_eq!(user.state(), AccountState::Locked);
+        assert_eq!(user.deactivated_at(), None);
+        assert_eq!(user.sign_in(PASSWORD), Err(AccountError::Locked));
+
+        user.unlock().unwrap();
+        assert_eq!(user.state(), AccountState::SignedOut);
+        assert_eq!(user.failed_attempts(), 0);
+        user.sign_in(PASSWORD).unwrap();
+    }
+
+    #[test]
+    fn invalid_moves_are_refused() {
+        let mut user = user();
+
+        // signed out
+        assert_eq!(user.sign_out(), Err(AccountError::NotSignedIn));
+        assert_eq!(user.reactivate(), Err(AccountError::NotDeactivated));
+        assert_eq!(user.unlock(), Err(AccountError::NotLocked));
+
+        // signed in
+        user.sign_in(PASSWORD).unwrap();
+        assert_eq!(user.sign_in(PASSWORD), Err(AccountError::AlreadySignedIn));
+        assert_eq!(user.unlock(), Err(AccountError::NotLocked));
+
+        // deactivated
+        user.deactivate().unwrap();
+        assert_eq!(user.sign_in(PASSWORD), Err(AccountError::Deactivated));
+        assert_eq!(user.deactivate(), Err(AccountError::AlreadyDeactivated));
+        assert_eq!(user.sign_out(), Err(AccountError::NotSignedIn));
+        assert_eq!(user.unlock(), Err(AccountError::NotLocked));
+
+        // a refused move changes nothing
+        assert_eq!(user.state(), AccountState::Deactivated);
+    }
+}
@@ -0,0 +1,304 @@
+// building a User one field at a time, with every value checked before the User exists
+//
+//   let user = UserBuilder::new()
+//       .name("Andrew Macmillan")
+//       .email("andrew@email.com")
+//       .age(33)
+//       .username("DaMan")
+//       .password("correct-horse-42")
+//       .build()?;
+//
+// each setter takes self by value and hands it back, which is what lets the calls be chained
+use std::fmt;
+
+use crate::user::{PasswordError, User};
+
+pub const MIN_AGE: i32 = 13;
+pub const MAX_AGE: i32 = 130;
+pub const MIN_USERNAME: usize = 3;
+pub const MAX_USERNAME: usize = 20;
+pub const MIN_PASSWORD: usize = 8;
+
+// one variant for every rule a new user can break
+#[derive(Debug, PartialEq, Eq)]
+pub enum UserError {
+    // a field that was never set (or was left empty)
+    Missing(&'static str),
+    // the email has no '@', nothing before it, or no '.' in the domain after it
+    InvalidEmail(String),
+    AgeOutOfRange { age: i32, min: i32, max: i32 },
+    UsernameLength { length: usize, min: usize, max: usize },
+    // usernames are letters, digits, '_' and '-'
+    UsernameCharacter(char),
+    // ... and start with a letter
+    UsernameStart(char),
+    PasswordTooShort { length: usize, min: usize },
+    PasswordNeedsLetter,
+    PasswordNeedsDigit,
+    // the password was fine, hashing it failed
+    Password(PasswordError),
+}
+
+impl fmt::Display for UserError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            UserError::Missing(field) => write!(f, "the {} is missing", field),
+            UserError::InvalidEmail(ref email) => write!(f, "'{}' is not a valid email address", email),
+            UserError::AgeOutOfRange { age, min, max } => {
+                write!(f, "an age of {} is not allowed (it must be {} to {})", age, min, max)
+            }
+            UserError::UsernameLength { length, min, max } => {
+                write!(f, "the username is {} characters long (it must be {} to {})", length, min, max)
+            }
+            UserError::UsernameCharacter(c) => write!(f, "the username can't contain '{}'", c),
+            UserError::UsernameStart(c) => write!(f, "the username must start with a letter, not '{}'", c),
+            UserError::PasswordTooShort { length, min } => {
+                write!(f, "the password is only {} characters long (it needs at least {})", length, min)
+            }
+            UserError::PasswordNeedsLetter => write!(f, "the password needs at least one letter"),
+            UserError::PasswordNeedsDigit => write!(f, "the password needs at least one digit"),
+            UserError::Password(ref e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<PasswordError> for UserError {
+    fn from(e: PasswordError) -> UserError {
+        UserError::Password(e)
+    }
+}
+
+// no #[derive(Debug)] here - the builder holds the password as plain text until build() hashes it
+#[derive(Default)]
+pub struct UserBuilder {
+    name: Option<String>,
+    email: Option<String>,
+    age: Option<i32>,
+    username: Option<String>,
+    password: Option<String>,
+}
+
+impl UserBuilder {
+    pub fn new() -> UserBuilder {
+        UserBuilder::default()
+    }
+
+    pub fn name(mut self, name: &str) -> UserBuilder {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn email(mut self, email: &str) -> UserBuilder {
+        self.email = Some(email.to_string());
+        self
+    }
+
+    pub fn age(mut self, age: i32) -> UserBuilder {
+        self.age = Some(age);
+        self
+    }
+
+    pub fn username(mut self, username: &str) -> UserBuilder {
+        self.username = Some(username.to_string());
+        self
+    }
+
+    pub fn password(mut self, password: &str) -> UserBuilder {
+        self.password = Some(password.to_string());
+        self
+    }
+
+    // check every field in order and stop at the first rule that is broken
+    pub fn build(self) -> Result<User, UserError> {
+        let name = required("name", self.name)?;
+        let email = required("email", self.email)?;
+        let age = self.age.ok_or(UserError::Missing("age"))?;
+        let username = required("username", self.username)?;
+        let password = required("password", self.password)?;
+
+        check_email(&email)?;
+        check_age(age)?;
+        check_username(&username)?;
+        check_password(&password)?;
+
+        Ok(User::new(name.trim(), email.trim(), age, &username, &password)?)
+    }
+}
+
+// a field that must be there and must not be blank
+fn required(field: &'static str, value: Option<String>) -> Result<String, UserError> {
+    match value {
+        Some(value) if !value.trim().is_empty() => Ok(value),
+        _ => Err(UserError::Missing(field)),
+    }
+}
+
+// a deliberately simple check: something@something.something with no spaces
+// the only real test of an email address is sending mail to it
+pub fn check_email(email: &str) -> Result<(), UserError> {
+    let email = email.trim();
+    let invalid = || UserError::InvalidEmail(email.to_string());
+
+    // split_once splits at the first '@', so a second '@' ends up in the domain and is caught below
+    let (local, domain) = email.split_once('@').ok_or_else(invalid)?;
+
+    let labels_ok = domain.split('.').count() >= 2 && domain.split('.').all(|label| !label.is_empty());
+    if local.is_empty() || domain.contains('@') || !labels_ok || email.chars().any(char::is_whitespace) {
+        return Err(invalid());
+    }
+
+    Ok(())
+}
+
+pub fn check_age(age: i32) -> Result<(), UserError> {
+    if (MIN_AGE..=MAX_AGE).contains(&age) {
+        Ok(())
+    } else {
+        Err(UserError::AgeOutOfRange {
+            age,
+            min: MIN_AGE,
+            max: MAX_AGE,
+        })
+    }
+}
+
+pub fn check_username(username: &str) -> Result<(), UserError> {
+    // chars().count() counts characters - len() would count bytes
+    let length = username.chars().count();
+    if !(MIN_USERNAME..=MAX_USERNAME).contains(&length) {
+        return Err(UserError::UsernameLength {
+            length,
+            min: MIN_USERNAME,
+            max: MAX_USERNAME,
+        });
+    }
+
+    if let Some(c) = username.chars().find(|&c| !(c.is_ascii_alphanumeric() || c == '_' || c == '-')) {
+        return Err(UserError::UsernameCharacter(c));
+    }
+
+    // the length check above means there is always a first character
+    match username.chars().next() {
+        Some(first) if !first.is_ascii_alphabetic() => Err(UserError::UsernameStart(first)),
+        _ => Ok(()),
+    }
+}
+
+pub fn check_password(password: &str) -> Result<(), UserError> {
+    let length = password.chars().count();
+    if length < MIN_PASSWORD {
+        return Err(UserError::PasswordTooShort {
+            length,
+            min: MIN_PASSWORD,
+        });
+    }
+
+    if !password.chars().any(char::is_alphabetic) {
+        return Err(UserError::PasswordNeedsLetter);
+    }
+
+    if !password.chars().any(|c| c.is_ascii_digit()) {
+        return Err(UserError::PasswordNeedsDigit);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a builder with every field valid, for the tests to break one rule at a time
+    fn valid() -> UserBuilder {
+        UserBuilder::new()
+            .name("Test User")
+            .email("test@example.com")
+            .age(30)
+            .username("tester")
+            .password("correct-horse-42")
+    }
+
+    // build() only gets as far as hashing when every rule passed, so the failing builds here are quick
+    fn error(builder: UserBuilder) -> UserError {
+        builder.build().map(|_| ()).unwrap_err()
+    }
+
+    #[test]
+    fn missing_or_blank_fields() {
+        assert_eq!(error(valid().name("   ")), UserError::Missing("name"));
+        assert_eq!(error(valid().email("")), UserError::Missing("email"));
+        assert_eq!(error(UserBuilder::new().name("Test User").email("test@example.com")), UserError::Missing("age"));
+        assert_eq!(error(UserBuilder::new().name("Test User")), UserError::Missing("email"));
+    }
+
+    #[test]
+    fn malformed_emails() {
+        let malformed = [
+            "no-at-sign",
+            "@example.com",
+            "test@example",
+            "test@@example.com",
+            "test@example.",
+            "a b@example.com",
+        ];
+        for email in malformed {
+            assert_eq!(check_email(email), Err(UserError::InvalidEmail(email.to_string())), "{}", email);
+        }
+        assert_eq!(error(valid().email("test@example")), UserError::InvalidEmail(String::from("test@example")));
+        assert_eq!(check_email(" test@example.com "), Ok(()));
+    }
+
+    #[test]
+    fn ages_out_of_range() {
+        let out_of_range = |age| UserError::AgeOutOfRange { age, min: MIN_AGE, max: MAX_AGE };
+
+        assert_eq!(error(valid().age(-4)), out_of_range(-4));
+        assert_eq!(check_age(MIN_AGE - 1), Err(out_of_range(MIN_AGE - 1)));
+        assert_eq!(check_age(MAX_AGE + 1), Err(out_of_range(MAX_AGE + 1)));
+        assert_eq!(check_age(MIN_AGE), Ok(()));
+        assert_eq!(check_age(MAX_AGE), Ok(()));
+    }
+
+    #[test]
+    fn bad_usernames() {
+        let length = |length| UserError::UsernameLength { length, min: MIN_USERNAME, max: MAX_USERNAME };
+
+        assert_eq!(error(valid().username("ab")), length(2));
+        assert_eq!(check_username(&"a".repeat(MAX_USERNAME + 1)), Err(length(MAX_USERNAME + 1)));
+        // the length is counted in characters - "éé" is 4 bytes but still too short
+        assert_eq!(check_username("éé"), Err(length(2)));
+        assert_eq!(check_username("abé"), Err(UserError::UsernameCharacter('é')));
+        assert_eq!(check_username("da man"), Err(UserError::UsernameCharacter(' ')));
+        assert_eq!(check_username("1daman"), Err(UserError::UsernameStart('1')));
+        assert_eq!(check_username("_daman"), Err(UserError::UsernameStart('_')));
+        assert_eq!(check_username("Da_Man-2"), Ok(()));
+    }
+
+    #[test]
+    fn weak_passwords() {
+        assert_eq!(error(valid().password("abc1")), UserError::PasswordTooShort { length: 4, min: MIN_PASSWORD });
+        assert_eq!(check_password("12345678"), Err(UserError::PasswordNeedsLetter));
+        assert_eq!(check_password("password"), Err(UserError::PasswordNeedsDigit));
+        assert_eq!(check_password("passw0rd"), Ok(()));
+    }
+
+    #[test]
+    fn the_setters_follow_the_same_rules() {
+        let mut user = valid().build().unwrap();
+
+        assert_eq!(user.set_name(" "), Err(UserError::Missing("name")));
+        assert_eq!(user.set_email("test@example"), Err(UserError::InvalidEmail(String::from("test@example"))));
+        assert_eq!(user.set_age(-4), Err(UserError::AgeOutOfRange { age: -4, min: MIN_AGE, max: MAX_AGE }));
+        assert_eq!(user.set_username("1daman"), Err(UserError::UsernameStart('1')));
+        assert_eq!(user.set_password("password"), Err(UserError::PasswordNeedsDigit));
+
+        // a refused value leaves the user as it was
+        assert_eq!((user.name(), user.email()), ("Test User", "test@example.com"));
+        assert_eq!((user.age(), user.username()), (30, "tester"));
+        assert!(user.verify_password("correct-horse-42"));
+
+        user.set_name("  Mr Berk ").unwrap();
+        user.set_email(" berk@example.com").unwrap();
+        assert_eq!((user.name(), user.email()), ("Mr Berk", "berk@example.com"));
+    }
+}
@@ -0,0 +1,14 @@
+// bring in the argon2 external crate for hashing passwords
+// (it needs its "std" feature for the OsRng random source the salts come from)
+extern crate argon2;
+// and rusqlite for saving users in an SQLite database file
+// (its "bundled" feature builds SQLite itself, so nothing needs installing)
+extern crate rusqlite;
+
+// the User type lives in the library half of this crate so that it can be tested and reused
+// main.rs is still where the struct and method examples are run
+pub mod account;
+pub mod builder;
+pub mod repository;
+pub mod sqlite;
+pub mod user;
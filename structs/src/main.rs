@@ -1,15 +1,8 @@
-// we can assign traits to structs using the derive syntax 
-#[derive(Debug)]
-struct User {
-    name: String,
-    email: String,
-    age: i32,
-    username: String,
-    password: String,
-    sign_in_count: u64,
-    active: bool,
-    logged_in: bool
-}
+// the User struct has moved to src/user.rs, in the library half of this crate
+extern crate structs;
+
+use structs::repository::{InMemoryUserRepository, UserRepository};
+use structs::user::User;
 
 #[derive(Debug)]
 struct Rectangle {
@@ -52,23 +45,61 @@ impl Rectangle {
 }
 
 fn main() {
-    let mut user_1 = User {
-        name: String::from("Andrew Macmillan"),
-        email: String::from("andrew@email.com"),
-        age: 33,
-        username: String::from("DaMan"),
-        password: String::from("12345678"),
-        sign_in_count: 10,
-        active: true,
-        logged_in: true,
-    };
-
-    user_1.name = String::from("Mr Berk");
+    // the builder checks every field before the User is made, and hashes the password
+    // so the plain text is never stored in the struct
+    let mut user_1 = User::builder()
+        .name("Andrew Macmillan")
+        .email("andrew@email.com")
+        .age(33)
+        .username("DaMan")
+        .password("correct-horse-42")
+        .build()
+        .expect("user_1 should be valid");
+
+    // signing in checks the password, counts the sign in and records when it happened
+    // a wrong password is counted too - enough of them in a row and the account is locked
+    if let Err(e) = user_1.sign_in("hunter2") {
+        println!("\ncould not sign in: {}", e);
+    }
+    user_1.sign_in("correct-horse-42").expect("the right password should sign in");
+
+    // the profile fields are private - a setter checks the new value first
+    user_1.set_name("Mr Berk").expect("a name that is not blank is fine");
 
     // :? will print all the values on one line
     // :#? will 'pretty print' the values as we see them in the code
+    // the password shows up as <redacted> - User implements Debug by hand to hide it
     println!("\nuser_1 details = {:#?}", user_1);
 
+    println!("\npassword 'correct-horse-42' correct? {}", user_1.verify_password("correct-horse-42"));
+    println!("password 'hunter2' correct? {}", user_1.verify_password("hunter2"));
+
+    // build() returns a Result, with a different error for each rule that was broken
+    let too_young = User::builder()
+        .name("Kid")
+        .email("kid@email.com")
+        .age(-4)
+        .username("kid")
+        .password("12345678")
+        .build();
+    match too_young {
+        Ok(user) => println!("\nunexpectedly valid: {:?}", user),
+        Err(e) => println!("\ncould not create the user: {}", e),
+    }
+
+    // a repository keeps users and makes sure no two share a username or an email
+    let mut users = InMemoryUserRepository::new();
+    users.create(user_1.clone()).expect("the repository starts empty");
+
+    // lookups ignore case, so "daman" finds "DaMan"
+    match users.get_by_username("daman") {
+        Ok(user) => println!("\nfound {} by username", user.name()),
+        Err(e) => println!("\n{}", e),
+    }
+    if let Err(e) = users.create(user_1.clone()) {
+        println!("could not add user_1 twice: {}", e);
+    }
+
     let rect_1 = Rectangle { width: 54.0, height: 100.0 };
 
     println!("\nArea of rect_1 = {}\n", rect_1.area());
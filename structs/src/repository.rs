@@ -0,0 +1,144 @@
+// somewhere to keep users
+//
+// callers only see the UserRepository trait, so the in-memory version here can later be swapped for one that
+// saves to disk without changing any of them
+// usernames and emails are both unique, and both are looked up ignoring case: "DaMan" and "daman" are the same user
+use std::fmt;
+
+use crate::user::User;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepositoryError {
+    DuplicateUsername(String),
+    DuplicateEmail(String),
+    // nobody has this username (or email)
+    NotFound(String),
+    // the storage itself failed, e.g. the database file could not be read
+    Storage(String),
+}
+
+impl fmt::Display for RepositoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RepositoryError::DuplicateUsername(ref username) => write!(f, "the username '{}' is already taken", username),
+            RepositoryError::DuplicateEmail(ref email) => write!(f, "the email '{}' is already in use", email),
+            RepositoryError::NotFound(ref key) => write!(f, "no user '{}'", key),
+            RepositoryError::Storage(ref message) => write!(f, "storage error: {}", message),
+        }
+    }
+}
+
+// the lookups hand back copies of the users rather than references,
+// because a repository that reads from disk has nothing to borrow from
+pub trait UserRepository {
+    fn create(&mut self, user: User) -> Result<(), RepositoryError>;
+
+    fn get_by_username(&self, username: &str) -> Result<User, RepositoryError>;
+
+    fn get_by_email(&self, email: &str) -> Result<User, RepositoryError>;
+
+    // replace the user stored under `username` - the new version may change the username or email,
+    // as long as they don't clash with anybody else's
+    fn update(&mut self, username: &str, user: User) -> Result<(), RepositoryError>;
+
+    // several updates as one: if any of them fails, none of them happen
+    // each item is (the username the user is stored under now, the new version of the user)
+    fn update_many(&mut self, updates: Vec<(String, User)>) -> Result<(), RepositoryError>;
+
+    // remove a user, handing back what was stored
+    fn delete(&mut self, username: &str) -> Result<User, RepositoryError>;
+
+    // every user, sorted by username
+    fn all(&self) -> Result<Vec<User>, RepositoryError>;
+}
+
+// the same text ignoring case - usernames are ASCII but emails need not be, so this uses full lowercase
+pub fn same_key(a: &str, b: &str) -> bool {
+    a.to_lowercase() == b.to_lowercase()
+}
+
+// users kept in a Vec for as long as the program runs
+#[derive(Debug, Default)]
+pub struct InMemoryUserRepository {
+    users: Vec<User>,
+}
+
+impl InMemoryUserRepository {
+    pub fn new() -> InMemoryUserRepository {
+        InMemoryUserRepository::default()
+    }
+
+    fn position(&self, username: &str) -> Option<usize> {
+        self.users.iter().position(|user| same_key(&user.username, username))
+    }
+
+    // check a user against everybody except the one at `skip` (the user being updated)
+    fn check_unique(&self, user: &User, skip: Option<usize>) -> Result<(), RepositoryError> {
+        // enumerate() pairs every user with its index so we can leave one out
+        let others = self.users.iter().enumerate().filter(|&(index, _)| Some(index) != skip);
+
+        for (_, other) in others {
+            if same_key(&other.username, &user.username) {
+                return Err(RepositoryError::DuplicateUsername(user.username.clone()));
+            }
+            if same_key(&other.email, &user.email) {
+                return Err(RepositoryError::DuplicateEmail(user.email.clone()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl UserRepository for InMemoryUserRepository {
+    fn create(&mut self, user: User) -> Result<(), RepositoryError> {
+        self.check_unique(&user, None)?;
+        self.users.push(user);
+        Ok(())
+    }
+
+    fn get_by_username(&self, username: &str) -> Result<User, RepositoryError> {
+        self.position(username)
+            .map(|index| self.users[index].clone())
+            .ok_or_else(|| RepositoryError::NotFound(username.to_string()))
+    }
+
+    fn get_by_email(&self, email: &str) -> Result<User, RepositoryError> {
+        self.users
+            .iter()
+            .find(|user| same_key(&user.email, email))
+            .cloned()
+            .ok_or_else(|| RepositoryError::NotFound(email.to_string()))
+    }
+
+    fn update(&mut self, username: &str, user: User) -> Result<(), RepositoryError> {
+        let index = self.position(username).ok_or_else(|| RepositoryError::NotFound(username.to_string()))?;
+        self.check_unique(&user, Some(index))?;
+        self.users[index] = user;
+        Ok(())
+    }
+
+    fn update_many(&mut self, updates: Vec<(String, User)>) -> Result<(), RepositoryError> {
+        // make the changes on a copy and only keep it if every one of them worked
+        let mut copy = InMemoryUserRepository {
+            users: self.users.clone(),
+        };
+        for (username, user) in updates {
+            copy.update(&username, user)?;
+        }
+
+        self.users = copy.users;
+        Ok(())
+    }
+
+    fn delete(&mut self, username: &str) -> Result<User, RepositoryError> {
+        let index = self.position(username).ok_or_else(|| RepositoryError::NotFound(username.to_string()))?;
+        Ok(self.users.remove(index))
+    }
+
+    fn all(&self) -> Result<Vec<User>, RepositoryError> {
+        let mut users = self.users.clone();
+        users.sort_by_key(|user| user.username.to_lowercase());
+        Ok(users)
+    }
+}
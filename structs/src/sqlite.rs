@@ -0,0 +1,261 @@
+// a UserRepository that keeps users in an SQLite database file, so they are still there next time
+//
+// the schema is built by a list of migrations: the database remembers how many have been run
+// (in SQLite's `user_version` setting) and opening it runs any that are new, each in its own transaction
+// usernames and emails are stored twice - as typed, and lowercased in a *_key column with a UNIQUE constraint -
+// so the database enforces the same case-insensitive rules as the in-memory repository
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rusqlite::{params, Connection, OptionalExtension, Row, Transaction};
+
+use crate::repository::{RepositoryError, UserRepository};
+use crate::user::User;
+
+// every change to the schema, oldest first - add new ones to the end and never edit the old ones
+const MIGRATIONS: &[&str] = &["
+    CREATE TABLE users (
+        id              INTEGER PRIMARY KEY,
+        name            TEXT    NOT NULL,
+        email           TEXT    NOT NULL,
+        email_key       TEXT    NOT NULL UNIQUE,
+        age             INTEGER NOT NULL,
+        username        TEXT    NOT NULL,
+        username_key    TEXT    NOT NULL UNIQUE,
+        password_hash   TEXT    NOT NULL,
+        state           TEXT    NOT NULL,
+        sign_in_count   INTEGER NOT NULL,
+        failed_attempts INTEGER NOT NULL,
+        -- times are milliseconds since 1970-01-01, or NULL for never
+        last_sign_in    INTEGER,
+        last_sign_out   INTEGER,
+        deactivated_at  INTEGER
+    );
+"];
+
+// every column except id, in the order the queries below use them
+const COLUMNS: &str = "name, email, age, username, password_hash, state, sign_in_count, failed_attempts, \
+                       last_sign_in, last_sign_out, deactivated_at";
+
+impl From<rusqlite::Error> for RepositoryError {
+    fn from(e: rusqlite::Error) -> RepositoryError {
+        RepositoryError::Storage(e.to_string())
+    }
+}
+
+pub struct SqliteUserRepository {
+    connection: Connection,
+}
+
+impl SqliteUserRepository {
+    // open the database file (creating it if needed) and bring its schema up to date
+    pub fn open(path: &Path) -> Result<SqliteUserRepository, RepositoryError> {
+        let mut connection = Connection::open(path)?;
+        migrate(&mut connection)?;
+        Ok(SqliteUserRepository { connection })
+    }
+
+    // how many migrations the database has had
+    pub fn schema_version(&self) -> Result<usize, RepositoryError> {
+        Ok(user_version(&self.connection)?)
+    }
+}
+
+fn user_version(connection: &Connection) -> rusqlite::Result<usize> {
+    connection.query_row("PRAGMA user_version", [], |row| row.get::<_, i64>(0).map(|v| v as usize))
+}
+
+fn migrate(connection: &mut Connection) -> Result<(), RepositoryError> {
+    let version = user_version(connection)?;
+    if version > MIGRATIONS.len() {
+        return Err(RepositoryError::Storage(format!(
+            "the database is at schema version {}, but this program only knows up to {}",
+            version,
+            MIGRATIONS.len()
+        )));
+    }
+
+    // skip() jumps over the migrations this database has already had
+    for (index, sql) in MIGRATIONS.iter().enumerate().skip(version) {
+        let transaction = connection.transaction()?;
+        transaction.execute_batch(sql)?;
+        transaction.pragma_update(None, "user_version", (index + 1) as i64)?;
+        transaction.commit()?;
+    }
+
+    Ok(())
+}
+
+// the lowercased copy of a username or email that the UNIQUE constraints are on
+fn key(text: &str) -> String {
+    text.to_lowercase()
+}
+
+fn to_millis(time: Option<SystemTime>) -> Option<i64> {
+    time.map(|time| time.duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or(0))
+}
+
+fn from_millis(millis: Option<i64>) -> Option<SystemTime> {
+    millis.map(|millis| UNIX_EPOCH + Duration::from_millis(millis.max(0) as u64))
+}
+
+// turn one row (selected with COLUMNS) back into a User
+fn read_user(row: &Row) -> rusqlite::Result<User> {
+    let state: String = row.get(5)?;
+    let state = state.parse().map_err(|e: String| {
+        rusqlite::Error::FromSqlConversionFailure(5, rusqlite::types::Type::Text, e.into())
+    })?;
+
+    Ok(User {
+        name: row.get(0)?,
+        email: row.get(1)?,
+        age: row.get(2)?,
+        username: row.get(3)?,
+        password_hash: row.get(4)?,
+        state,
+        sign_in_count: row.get::<_, i64>(6)? as u64,
+        failed_attempts: row.get(7)?,
+        last_sign_in: from_millis(row.get(8)?),
+        last_sign_out: from_millis(row.get(9)?),
+        deactivated_at: from_millis(row.get(10)?),
+    })
+}
+
+// the row id of the user stored under this username
+fn find_id(transaction: &Transaction, username: &str) -> Result<i64, RepositoryError> {
+    transaction
+        .query_row("SELECT id FROM users WHERE username_key = ?1", params![key(username)], |row| row.get(0))
+        .optional()?
+        .ok_or_else(|| RepositoryError::NotFound(username.to_string()))
+}
+
+// the same checks as the in-memory repository, so the caller gets a DuplicateUsername or DuplicateEmail
+// instead of a generic constraint error - `skip` is the row being updated
+fn check_unique(transaction: &Transaction, user: &User, skip: Option<i64>) -> Result<(), RepositoryError> {
+    let taken = |column: &str, value: &str| -> rusqlite::Result<bool> {
+        let sql = format!("SELECT COUNT(*) FROM users WHERE {} = ?1 AND id IS NOT ?2", column);
+        transaction.query_row(&sql, params![key(value), skip], |row| row.get::<_, i64>(0)).map(|n| n > 0)
+    };
+
+    if taken("username_key", &user.username)? {
+        return Err(RepositoryError::DuplicateUsername(user.username.clone()));
+    }
+    if taken("email_key", &user.email)? {
+        return Err(RepositoryError::DuplicateEmail(user.email.clone()));
+    }
+
+    Ok(())
+}
+
+fn update_in(transaction: &Transaction, username: &str, user: &User) -> Result<(), RepositoryError> {
+    let id = find_id(transaction, username)?;
+    check_unique(transaction, user, Some(id))?;
+
+    transaction.execute(
+        "UPDATE users SET name = ?1, email = ?2, email_key = ?3, age = ?4, username = ?5, username_key = ?6,
+            password_hash = ?7, state = ?8, sign_in_count = ?9, failed_attempts = ?10,
+            last_sign_in = ?11, last_sign_out = ?12, deactivated_at = ?13
+         WHERE id = ?14",
+        params![
+            user.name,
+            user.email,
+            key(&user.email),
+            user.age,
+            user.username,
+            key(&user.username),
+            user.password_hash,
+            user.state.name(),
+            user.sign_in_count as i64,
+            user.failed_attempts,
+            to_millis(user.last_sign_in),
+            to_millis(user.last_sign_out),
+            to_millis(user.deactivated_at),
+            id,
+        ],
+    )?;
+
+    Ok(())
+}
+
+impl UserRepository for SqliteUserRepository {
+    fn create(&mut self, user: User) -> Result<(), RepositoryError> {
+        // the check and the insert share a transaction, so nobody can sneak the same username in between them
+        let transaction = self.connection.transaction()?;
+        check_unique(&transaction, &user, None)?;
+
+        transaction.execute(
+            "INSERT INTO users (name, email, email_key, age, username, username_key, password_hash, state,
+                sign_in_count, failed_attempts, last_sign_in, last_sign_out, deactivated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                user.name,
+                user.email,
+                key(&user.email),
+                user.age,
+                user.username,
+                key(&user.username),
+                user.password_hash,
+                user.state.name(),
+                user.sign_in_count as i64,
+                user.failed_attempts,
+                to_millis(user.last_sign_in),
+                to_millis(user.last_sign_out),
+                to_millis(user.deactivated_at),
+            ],
+        )?;
+
+        Ok(transaction.commit()?)
+    }
+
+    fn get_by_username(&self, username: &str) -> Result<User, RepositoryError> {
+        let sql = format!("SELECT {} FROM users WHERE username_key = ?1", COLUMNS);
+        self.connection
+            .query_row(&sql, params![key(username)], read_user)
+            .optional()?
+            .ok_or_else(|| RepositoryError::NotFound(username.to_string()))
+    }
+
+    fn get_by_email(&self, email: &str) -> Result<User, RepositoryError> {
+        let sql = format!("SELECT {} FROM users WHERE email_key = ?1", COLUMNS);
+        self.connection
+            .query_row(&sql, params![key(email)], read_user)
+            .optional()?
+            .ok_or_else(|| RepositoryError::NotFound(email.to_string()))
+    }
+
+    fn update(&mut self, username: &str, user: User) -> Result<(), RepositoryError> {
+        let transaction = self.connection.transaction()?;
+        update_in(&transaction, username, &user)?;
+        Ok(transaction.commit()?)
+    }
+
+    // a transaction that is dropped without commit() is rolled back, so returning early with ? undoes everything
+    fn update_many(&mut self, updates: Vec<(String, User)>) -> Result<(), RepositoryError> {
+        let transaction = self.connection.transaction()?;
+        for (username, user) in &updates {
+            update_in(&transaction, username, user)?;
+        }
+        Ok(transaction.commit()?)
+    }
+
+    fn delete(&mut self, username: &str) -> Result<User, RepositoryError> {
+        let transaction = self.connection.transaction()?;
+
+        let sql = format!("SELECT {} FROM users WHERE username_key = ?1", COLUMNS);
+        let user = transaction
+            .query_row(&sql, params![key(username)], read_user)
+            .optional()?
+            .ok_or_else(|| RepositoryError::NotFound(username.to_string()))?;
+        transaction.execute("DELETE FROM users WHERE username_key = ?1", params![key(username)])?;
+
+        transaction.commit()?;
+        Ok(user)
+    }
+
+    fn all(&self) -> Result<Vec<User>, RepositoryError> {
+        let sql = format!("SELECT {} FROM users ORDER BY username_key", COLUMNS);
+        let mut statement = self.connection.prepare(&sql)?;
+        let users = statement.query_map([], read_user)?.collect::<rusqlite::Result<Vec<User>>>()?;
+        Ok(users)
+    }
+}
@@ -0,0 +1,228 @@
+// a user account
+// the password is never kept as text - only a salted Argon2 hash of it, which is slow to guess on purpose
+use std::fmt;
+use std::time::SystemTime;
+
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{self, PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+
+use crate::account::AccountState;
+use crate::builder::{self, UserBuilder, UserError};
+
+// what it looks like when the password field is printed
+const REDACTED: &str = "<redacted>";
+
+// Clone is derived, Debug is written by hand further down
+#[derive(Clone)]
+pub struct User {
+    // private, so the builder's rules can't be side-stepped - they change through the set_* methods below
+    pub(crate) name: String,
+    pub(crate) email: String,
+    pub(crate) age: i32,
+    pub(crate) username: String,
+    // a PHC string like "$argon2id$v=19$m=19456,t=2,p=1$<salt>$<hash>"
+    // the salt and the settings travel with the hash, so verifying needs nothing else
+    pub(crate) password_hash: String,
+    // the sign-in lifecycle - these only change through the methods in account.rs
+    pub(crate) state: AccountState,
+    pub(crate) sign_in_count: u64,
+    pub(crate) failed_attempts: u32,
+    pub(crate) last_sign_in: Option<SystemTime>,
+    pub(crate) last_sign_out: Option<SystemTime>,
+    pub(crate) deactivated_at: Option<SystemTime>,
+}
+
+// hashing can only fail if the Argon2 settings are broken, but that is still worth reporting
+#[derive(Debug, PartialEq, Eq)]
+pub enum PasswordError {
+    Hashing(password_hash::Error),
+}
+
+impl fmt::Display for PasswordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PasswordError::Hashing(ref e) => write!(f, "could not hash the password: {}", e),
+        }
+    }
+}
+
+impl From<password_hash::Error> for PasswordError {
+    fn from(e: password_hash::Error) -> PasswordError {
+        PasswordError::Hashing(e)
+    }
+}
+
+// hash a password with a fresh random salt
+// the same password hashed twice gives two different strings, so equal hashes never give away equal passwords
+fn hash_password(password: &str) -> Result<String, PasswordError> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default().hash_password(password.as_bytes(), &salt)?;
+    Ok(hash.to_string())
+}
+
+impl User {
+    // the way to make a new User from outside this crate - see builder.rs
+    pub fn builder() -> UserBuilder {
+        UserBuilder::new()
+    }
+
+    // Associated function - the password is hashed straight away and the plain text is dropped
+    // nothing is checked here, so only the builder (which checks everything first) calls it
+    pub(crate) fn new(name: &str, email: &str, age: i32, username: &str, password: &str) -> Result<User, PasswordError> {
+        Ok(User {
+            name: name.to_string(),
+            email: email.to_string(),
+            age,
+            username: username.to_string(),
+            password_hash: hash_password(password)?,
+            state: AccountState::SignedOut,
+            sign_in_count: 0,
+            failed_attempts: 0,
+            last_sign_in: None,
+            last_sign_out: None,
+            deactivated_at: None,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+
+    pub fn age(&self) -> i32 {
+        self.age
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    // the setters check the new value with the same rules as the builder and leave the user unchanged if it fails
+    pub fn set_name(&mut self, name: &str) -> Result<(), UserError> {
+        if name.trim().is_empty() {
+            return Err(UserError::Missing("name"));
+        }
+        self.name = name.trim().to_string();
+        Ok(())
+    }
+
+    pub fn set_email(&mut self, email: &str) -> Result<(), UserError> {
+        builder::check_email(email)?;
+        self.email = email.trim().to_string();
+        Ok(())
+    }
+
+    pub fn set_age(&mut self, age: i32) -> Result<(), UserError> {
+        builder::check_age(age)?;
+        self.age = age;
+        Ok(())
+    }
+
+    pub fn set_username(&mut self, username: &str) -> Result<(), UserError> {
+        builder::check_username(username)?;
+        self.username = username.to_string();
+        Ok(())
+    }
+
+    // replace the password - &mut self because this changes the instance
+    // the new password has to pass the same strength rules as the builder's
+    pub fn set_password(&mut self, password: &str) -> Result<(), UserError> {
+        builder::check_password(password)?;
+        self.password_hash = hash_password(password)?;
+        Ok(())
+    }
+
+    // check a password against the stored hash
+    // a hash that can't even be read counts as a wrong password rather than an error
+    pub fn verify_password(&self, password: &str) -> bool {
+        match PasswordHash::new(&self.password_hash) {
+            Ok(hash) => Argon2::default().verify_password(password.as_bytes(), &hash).is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    // the stored hash, for saving the user somewhere - it is safe to store but still should not be shown
+    pub fn password_hash(&self) -> &str {
+        &self.password_hash
+    }
+}
+
+// Debug is written by hand instead of derived, so that {:?} and {:#?} never print the password hash
+impl fmt::Debug for User {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("User")
+            .field("name", &self.name)
+            .field("email", &self.email)
+            .field("age", &self.age)
+            .field("username", &self.username)
+            .field("password", &format_args!("{}", REDACTED))
+            .field("state", &self.state)
+            .field("sign_in_count", &self.sign_in_count)
+            .field("failed_attempts", &self.failed_attempts)
+            .field("last_sign_in", &self.last_sign_in)
+            .field("last_sign_out", &self.last_sign_out)
+            .field("deactivated_at", &self.deactivated_at)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSWORD: &str = "correct-horse-42";
+
+    fn user() -> User {
+        User::builder()
+            .name("Test User")
+            .email("test@example.com")
+            .age(30)
+            .username("tester")
+            .password(PASSWORD)
+            .build()
+            .expect("a valid user")
+    }
+
+    #[test]
+    fn debug_never_shows_the_password_or_its_hash() {
+        let user = user();
+
+        for text in [format!("{:?}", user), format!("{:#?}", user)] {
+            assert!(!text.contains(PASSWORD), "{}", text);
+            assert!(!text.contains(user.password_hash()), "{}", text);
+            assert!(text.contains(REDACTED), "{}", text);
+        }
+    }
+
+    #[test]
+    fn only_the_right_password_verifies() {
+        let user = user();
+
+        assert!(user.verify_password(PASSWORD));
+        assert!(!user.verify_password("correct-horse-43"));
+        assert!(!user.verify_password(""));
+    }
+
+    #[test]
+    fn the_same_password_hashes_differently_every_time() {
+        let first = hash_password(PASSWORD).unwrap();
+        let second = hash_password(PASSWORD).unwrap();
+
+        assert_ne!(first, second);
+        assert!(first.starts_with("$argon2id$"));
+    }
+
+    #[test]
+    fn a_new_password_replaces_the_old_one() {
+        let mut user = user();
+
+        user.set_password("battery-staple-7").unwrap();
+
+        assert!(!user.verify_password(PASSWORD));
+        assert!(user.verify_password("battery-staple-7"));
+    }
+}
@@ -0,0 +1,167 @@
+// the rules every UserRepository has to follow, written once and run against each backend
+// a new backend only needs one more #[test] at the bottom that hands check_all a way to make an empty one
+extern crate structs;
+
+use std::path::Path;
+use std::sync::OnceLock;
+
+use structs::account::AccountState;
+use structs::repository::{InMemoryUserRepository, RepositoryError, UserRepository};
+use structs::sqlite::SqliteUserRepository;
+use structs::user::User;
+
+// Argon2 is slow on purpose, so the password is hashed once and every test user is a copy with new details
+fn user(username: &str, email: &str) -> User {
+    static TEMPLATE: OnceLock<User> = OnceLock::new();
+
+    let mut user = TEMPLATE
+        .get_or_init(|| {
+            User::builder()
+                .name("Test User")
+                .email("template@example.com")
+                .age(30)
+                .username("template")
+                .password("correct-horse-42")
+                .build()
+                .expect("a valid user")
+        })
+        .clone();
+    user.set_username(username).unwrap();
+    user.set_email(email).unwrap();
+    user
+}
+
+fn not_found(key: &str) -> RepositoryError {
+    RepositoryError::NotFound(key.to_string())
+}
+
+// the usernames in the order all() gives them
+fn usernames<R: UserRepository>(users: &R) -> Vec<String> {
+    users.all().unwrap().iter().map(|user| user.username().to_string()).collect()
+}
+
+// every check gets a brand new, empty repository from `fresh`
+fn check_all<R: UserRepository, F: FnMut() -> R>(mut fresh: F) {
+    usernames_and_emails_are_unique_ignoring_case(&mut fresh());
+    lookups_ignore_case(&mut fresh());
+    missing_users_are_not_found(&mut fresh());
+    update_can_rename_but_not_onto_somebody_else(&mut fresh());
+    delete_hands_back_the_user(&mut fresh());
+    a_failed_update_many_changes_nothing(&mut fresh());
+}
+
+fn usernames_and_emails_are_unique_ignoring_case<R: UserRepository>(users: &mut R) {
+    users.create(user("alice", "alice@example.com")).unwrap();
+
+    assert_eq!(
+        users.create(user("ALICE", "other@example.com")),
+        Err(RepositoryError::DuplicateUsername(String::from("ALICE")))
+    );
+    assert_eq!(
+        users.create(user("bob", "Alice@Example.com")),
+        Err(RepositoryError::DuplicateEmail(String::from("Alice@Example.com")))
+    );
+    assert_eq!(usernames(users), vec!["alice"]);
+}
+
+fn lookups_ignore_case<R: UserRepository>(users: &mut R) {
+    users.create(user("DaMan", "Andrew@Email.com")).unwrap();
+
+    // the lookup key can be in any case, but the user comes back exactly as it was stored
+    let found = users.get_by_username("daman").unwrap();
+    assert_eq!(found.username(), "DaMan");
+    assert_eq!(found.email(), "Andrew@Email.com");
+    assert_eq!(users.get_by_email("ANDREW@email.COM").unwrap().username(), "DaMan");
+}
+
+fn missing_users_are_not_found<R: UserRepository>(users: &mut R) {
+    assert_eq!(users.get_by_username("carol").unwrap_err(), not_found("carol"));
+    assert_eq!(users.get_by_email("carol@example.com").unwrap_err(), not_found("carol@example.com"));
+    assert_eq!(users.update("carol", user("carol", "carol@example.com")), Err(not_found("carol")));
+    assert_eq!(users.delete("carol").unwrap_err(), not_found("carol"));
+    assert!(users.all().unwrap().is_empty());
+}
+
+fn update_can_rename_but_not_onto_somebody_else<R: UserRepository>(users: &mut R) {
+    users.create(user("alice", "alice@example.com")).unwrap();
+    users.create(user("bob", "bob@example.com")).unwrap();
+
+    // an update that keeps its own username and email does not clash with itself
+    let mut alice = users.get_by_username("alice").unwrap();
+    alice.set_age(31).unwrap();
+    users.update("alice", alice).unwrap();
+    assert_eq!(users.get_by_username("alice").unwrap().age(), 31);
+
+    let mut alice = users.get_by_username("alice").unwrap();
+    alice.set_username("alicia").unwrap();
+    alice.deactivate().unwrap();
+    users.update("ALICE", alice).unwrap();
+
+    assert_eq!(users.get_by_username("alice").unwrap_err(), not_found("alice"));
+    let alicia = users.get_by_username("alicia").unwrap();
+    assert_eq!(alicia.state(), AccountState::Deactivated);
+    assert!(alicia.deactivated_at().is_some());
+
+    let mut bob = users.get_by_username("bob").unwrap();
+    bob.set_username("Alicia").unwrap();
+    assert_eq!(users.update("bob", bob), Err(RepositoryError::DuplicateUsername(String::from("Alicia"))));
+
+    let mut bob = users.get_by_username("bob").unwrap();
+    bob.set_email("ALICE@example.com").unwrap();
+    assert_eq!(
+        users.update("bob", bob),
+        Err(RepositoryError::DuplicateEmail(String::from("ALICE@example.com")))
+    );
+
+    assert_eq!(usernames(users), vec!["alicia", "bob"]);
+}
+
+fn delete_hands_back_the_user<R: UserRepository>(users: &mut R) {
+    users.create(user("carol", "carol@example.com")).unwrap();
+    users.create(user("alice", "alice@example.com")).unwrap();
+    users.create(user("Bob", "bob@example.com")).unwrap();
+
+    // all() is sorted by username, ignoring case
+    assert_eq!(usernames(users), vec!["alice", "Bob", "carol"]);
+
+    assert_eq!(users.delete("BOB").unwrap().username(), "Bob");
+    assert_eq!(users.delete("bob").unwrap_err(), not_found("bob"));
+    assert_eq!(usernames(users), vec!["alice", "carol"]);
+
+    // the username and email are free again once their user is gone
+    users.create(user("bob", "bob@example.com")).unwrap();
+}
+
+fn a_failed_update_many_changes_nothing<R: UserRepository>(users: &mut R) {
+    users.create(user("alice", "alice@example.com")).unwrap();
+    users.create(user("bob", "bob@example.com")).unwrap();
+
+    let mut alice = users.get_by_username("alice").unwrap();
+    alice.set_age(31).unwrap();
+    // the second update takes alice's email, so the whole batch has to be rolled back
+    let mut bob = users.get_by_username("bob").unwrap();
+    bob.set_email("ALICE@example.com").unwrap();
+
+    let result = users.update_many(vec![(String::from("alice"), alice.clone()), (String::from("bob"), bob)]);
+    assert!(matches!(result, Err(RepositoryError::DuplicateEmail(_))));
+    assert_eq!(users.get_by_username("alice").unwrap().age(), 30);
+    assert_eq!(users.get_by_username("bob").unwrap().email(), "bob@example.com");
+
+    // and a batch that is fine goes through completely
+    let mut bob = users.get_by_username("bob").unwrap();
+    bob.set_age(45).unwrap();
+    users.update_many(vec![(String::from("alice"), alice), (String::from("bob"), bob)]).unwrap();
+    assert_eq!(users.get_by_username("alice").unwrap().age(), 31);
+    assert_eq!(users.get_by_username("bob").unwrap().age(), 45);
+}
+
+#[test]
+fn in_memory() {
+    check_all(InMemoryUserRepository::new);
+}
+
+// ":memory:" gives every connection a private database that disappears when it is closed
+#[test]
+fn sqlite() {
+    check_all(|| SqliteUserRepository::open(Path::new(":memory:")).expect("open an in-memory database"));
+}
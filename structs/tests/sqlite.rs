@@ -0,0 +1,101 @@
+// round-trip tests for the SQLite repository, each against its own database file in the temp directory
+// the rules every repository shares (uniqueness, lookups, updates, deletes) are checked in repository.rs
+extern crate rusqlite;
+extern crate structs;
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use structs::account::AccountState;
+use structs::repository::{RepositoryError, UserRepository};
+use structs::sqlite::SqliteUserRepository;
+use structs::user::User;
+
+// a database file that is deleted again when the test finishes (even if it fails)
+struct TempDb {
+    path: PathBuf,
+}
+
+impl TempDb {
+    // the process id keeps parallel test runs apart, the name keeps the tests in one run apart
+    fn new(name: &str) -> TempDb {
+        let path = env::temp_dir().join(format!("structs-{}-{}.db", process::id(), name));
+        let _ = fs::remove_file(&path);
+        TempDb { path }
+    }
+
+    fn open(&self) -> SqliteUserRepository {
+        SqliteUserRepository::open(&self.path).expect("open the database")
+    }
+}
+
+impl Drop for TempDb {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn user(username: &str, email: &str) -> User {
+    User::builder()
+        .name("Test User")
+        .email(email)
+        .age(30)
+        .username(username)
+        .password("correct-horse-42")
+        .build()
+        .expect("a valid user")
+}
+
+#[test]
+fn users_survive_closing_and_reopening() {
+    let db = TempDb::new("reopen");
+
+    let mut alice = user("Alice", "Alice@Example.com");
+    alice.sign_in("wrong-password-1").unwrap_err();
+    alice.sign_in("correct-horse-42").unwrap();
+
+    {
+        let mut users = db.open();
+        users.create(alice.clone()).unwrap();
+    }
+
+    // a brand new connection has to read everything back from the file
+    let users = db.open();
+    let loaded = users.get_by_username("alice").unwrap();
+
+    assert_eq!(loaded.name(), alice.name());
+    assert_eq!(loaded.email(), "Alice@Example.com");
+    assert_eq!(loaded.username(), "Alice");
+    assert_eq!(loaded.age(), 30);
+    assert_eq!(loaded.state(), AccountState::SignedIn);
+    assert_eq!(loaded.sign_in_count(), 1);
+    assert_eq!(loaded.failed_attempts(), 0);
+    assert!(loaded.last_sign_in().is_some());
+    assert_eq!(loaded.last_sign_out(), None);
+    assert_eq!(loaded.password_hash(), alice.password_hash());
+    assert!(loaded.verify_password("correct-horse-42"));
+
+    assert_eq!(users.get_by_email("ALICE@example.COM").unwrap().username(), "Alice");
+}
+
+#[test]
+fn the_schema_is_created_once_and_newer_schemas_are_refused() {
+    let db = TempDb::new("schema");
+
+    let version = db.open().schema_version().unwrap();
+    assert!(version >= 1);
+    // opening again must not try to create the table a second time
+    assert_eq!(db.open().schema_version().unwrap(), version);
+
+    // pretend a newer version of the program has been at the file
+    let connection = rusqlite::Connection::open(&db.path).unwrap();
+    connection.pragma_update(None, "user_version", (version + 1) as i64).unwrap();
+    drop(connection);
+
+    assert!(matches!(
+        SqliteUserRepository::open(&db.path),
+        Err(RepositoryError::Storage(_))
+    ));
+}